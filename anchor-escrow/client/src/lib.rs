@@ -0,0 +1,58 @@
+use std::future::Future;
+
+use anchor_lang::{prelude::Pubkey, solana_program::instruction::AccountMeta};
+use anchor_spl::token_interface::spl_token_2022::offchain::{
+    create_transfer_checked_instruction_with_extra_metas, AccountDataResult, AccountFetchError,
+};
+
+pub use anchor_escrow::ID;
+
+pub fn escrow_pda(maker: &Pubkey, seed: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"escrow", maker.as_ref(), &seed.to_le_bytes()], &ID)
+}
+
+pub fn config_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"config"], &ID)
+}
+
+/// Extra accounts a transfer of `mint` needs when the mint has a transfer
+/// hook, in the order the program expects them in `remaining_accounts`.
+/// Empty for mints without a hook.
+///
+/// `take` moves both mints, so append the accounts for mint b (taker to
+/// maker), mint a (vault to taker) and, while the offer carries a protocol
+/// fee, mint a again from the vault to the treasury's token account, whose
+/// hook accounts can differ; duplicates are harmless.
+/// `fetch_account_data` looks up an account's data, `Ok(None)` if it doesn't
+/// exist, usually backed by an rpc `get_account` call.
+#[allow(clippy::too_many_arguments)]
+pub async fn transfer_hook_accounts<F, Fut>(
+    token_program: &Pubkey,
+    source: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    amount: u64,
+    decimals: u8,
+    fetch_account_data: F,
+) -> Result<Vec<AccountMeta>, AccountFetchError>
+where
+    F: Fn(Pubkey) -> Fut,
+    Fut: Future<Output = AccountDataResult>,
+{
+    let instruction = create_transfer_checked_instruction_with_extra_metas(
+        token_program,
+        source,
+        mint,
+        destination,
+        authority,
+        &[],
+        amount,
+        decimals,
+        fetch_account_data,
+    )
+    .await?;
+
+    // skip source, mint, destination and authority
+    Ok(instruction.accounts.into_iter().skip(4).collect())
+}
@@ -10,4 +10,26 @@ pub enum EscrowError {
     InvalidMintA,
     #[msg("invalid mint b")]
     InvalidMintB,
+    #[msg("invalid expiry")]
+    InvalidExpiry,
+    #[msg("offer expired")]
+    OfferExpired,
+    #[msg("offer not expired")]
+    OfferNotExpired,
+    #[msg("invalid taker")]
+    InvalidTaker,
+    #[msg("transfer fee changed")]
+    TransferFeeChanged,
+    #[msg("offer changed")]
+    OfferChanged,
+    #[msg("invalid basket")]
+    InvalidBasket,
+    #[msg("invalid leg accounts")]
+    InvalidLegAccounts,
+    #[msg("invalid protocol fee")]
+    InvalidProtocolFee,
+    #[msg("invalid admin")]
+    InvalidAdmin,
+    #[msg("invalid treasury")]
+    InvalidTreasury,
 }
@@ -0,0 +1,145 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    errors::EscrowError,
+    instructions::{mint_fee, transfer_checked_with_hook},
+    state::Escrow,
+};
+
+#[derive(Accounts)]
+pub struct Amend<'info> {
+    // other accounts
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = ["escrow".as_bytes(), maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+        has_one = maker @ EscrowError::InvalidMaker,
+        has_one = mint_a @ EscrowError::InvalidMintA,
+        has_one = mint_b @ EscrowError::InvalidMintB,
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    // token accounts
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub mint_a: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = maker,
+        associated_token::mint = mint_a,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program
+    )]
+    pub maker_ata_token_a: InterfaceAccount<'info, TokenAccount>,
+
+    // programs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Amend<'info> {
+    pub fn deposit(&self, amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        transfer_checked_with_hook(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.maker_ata_token_a.to_account_info(),
+                    to: self.vault.to_account_info(),
+                    authority: self.maker.to_account_info(),
+                    mint: self.mint_a.to_account_info(),
+                },
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec()),
+            amount,
+            self.mint_a.decimals,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn withdraw(&self, amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        // refund closes the offer, an amend has to leave something to sell
+        require_gt!(self.vault.amount, amount, EscrowError::InvalidAmount);
+
+        transfer_checked_with_hook(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.vault.to_account_info(),
+                    to: self.maker_ata_token_a.to_account_info(),
+                    authority: self.escrow.to_account_info(),
+                    mint: self.mint_a.to_account_info(),
+                },
+                &[&[
+                    "escrow".as_bytes(),
+                    self.maker.key().as_ref(),
+                    self.escrow.seed.to_le_bytes().as_ref(),
+                    &[self.escrow.bump],
+                ]],
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec()),
+            amount,
+            self.mint_a.decimals,
+        )?;
+
+        Ok(())
+    }
+
+    /// Re-quotes the offer against the current fees, the maker signing an
+    /// amend accepts whatever the fee authorities have set by now.
+    pub fn update_escrow(&mut self, recieve: u64) -> Result<()> {
+        self.vault.reload()?;
+
+        self.escrow.recieve = recieve;
+        self.escrow.deposited = self.vault.amount;
+        self.escrow.fee_a = mint_fee(&self.mint_a)?;
+        self.escrow.fee_b = mint_fee(&self.mint_b)?;
+
+        Ok(())
+    }
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, Amend<'info>>,
+    recieve: u64,
+    deposit: u64,
+    withdraw: u64,
+) -> Result<()> {
+    require!(recieve > 0, EscrowError::InvalidAmount);
+    require!(deposit == 0 || withdraw == 0, EscrowError::InvalidAmount);
+
+    if deposit > 0 {
+        ctx.accounts.deposit(deposit, ctx.remaining_accounts)?;
+    }
+
+    if withdraw > 0 {
+        ctx.accounts.withdraw(withdraw, ctx.remaining_accounts)?;
+    }
+
+    ctx.accounts.update_escrow(recieve)?;
+
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        close_account, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+    },
+};
+
+use crate::{
+    errors::EscrowError,
+    instructions::{harvest_withheld_fees, transfer_checked_with_hook},
+    state::Escrow,
+};
+
+#[derive(Accounts)]
+pub struct CrankExpired<'info> {
+    // other accounts
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = maker, // send rent back to maker
+        seeds = ["escrow".as_bytes(), maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+        has_one = maker @ EscrowError::InvalidMaker,
+        has_one = mint_a @ EscrowError::InvalidMintA,
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    // token accounts
+    #[account(
+        mut,
+        mint::token_program = token_program
+    )]
+    pub mint_a: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        associated_token::mint = mint_a,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program
+    )]
+    pub maker_ata_token_a: InterfaceAccount<'info, TokenAccount>,
+
+    // programs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CrankExpired<'info> {
+    pub fn check_expired(&self) -> Result<()> {
+        require!(
+            self.escrow.is_expired(Clock::get()?.unix_timestamp),
+            EscrowError::OfferNotExpired
+        );
+
+        Ok(())
+    }
+
+    pub fn transfer_to_maker_and_close_vault(
+        &self,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        transfer_checked_with_hook(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.vault.to_account_info(),
+                    to: self.maker_ata_token_a.to_account_info(),
+                    authority: self.escrow.to_account_info(),
+                    mint: self.mint_a.to_account_info(),
+                },
+                &[&[
+                    "escrow".as_bytes(),
+                    self.maker.key().as_ref(),
+                    self.escrow.seed.to_le_bytes().as_ref(),
+                    &[self.escrow.bump],
+                ]],
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec()),
+            self.vault.amount,
+            self.mint_a.decimals,
+        )?;
+
+        harvest_withheld_fees(
+            self.token_program.to_account_info(),
+            self.mint_a.to_account_info(),
+            self.vault.to_account_info(),
+        )?;
+
+        close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.vault.to_account_info(),
+                destination: self.maker.to_account_info(),
+                authority: self.escrow.to_account_info(),
+            },
+            &[&[
+                "escrow".as_bytes(),
+                self.maker.key().as_ref(),
+                self.escrow.seed.to_le_bytes().as_ref(),
+                &[self.escrow.bump],
+            ]],
+        ))?;
+
+        Ok(())
+    }
+
+    pub fn pay_crank_tip(&self) -> Result<()> {
+        let tip = self.escrow.crank_tip;
+
+        // the escrow is program owned, so the tip moves without a cpi
+        self.escrow.sub_lamports(tip)?;
+        self.cranker.add_lamports(tip)?;
+
+        Ok(())
+    }
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, CrankExpired<'info>>) -> Result<()> {
+    ctx.accounts.check_expired()?;
+    ctx.accounts
+        .transfer_to_maker_and_close_vault(ctx.remaining_accounts)?;
+    ctx.accounts.pay_crank_tip()?;
+
+    Ok(())
+}
@@ -0,0 +1,235 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{create_idempotent, get_associated_token_address_with_program_id, Create},
+    token_2022_extensions::transfer_fee::{
+        harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint,
+    },
+    token_interface::{
+        close_account, get_mint_extension_data,
+        spl_token_2022::{
+            self,
+            extension::transfer_fee::{TransferFee, TransferFeeConfig},
+            onchain::invoke_transfer_checked,
+        },
+        CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+    },
+};
+
+use crate::{
+    errors::EscrowError,
+    state::{protocol_fee, BasketEscrow, MintFee},
+};
+
+/// `transfer_checked` that also forwards the context's remaining accounts, so
+/// mints with a transfer hook get the extra accounts their hook resolves to.
+/// Behaves exactly like the plain cpi for mints without one.
+pub fn transfer_checked_with_hook<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    invoke_transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+        &ctx.remaining_accounts,
+        amount,
+        decimals,
+        ctx.signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
+/// Transfer fee the mint charges this epoch, zero for legacy mints and
+/// Token-2022 mints without the fee extension.
+pub fn mint_fee(mint: &InterfaceAccount<Mint>) -> Result<MintFee> {
+    let info = mint.to_account_info();
+
+    if *info.owner != spl_token_2022::ID {
+        return Ok(MintFee::default());
+    }
+
+    let Ok(config) = get_mint_extension_data::<TransferFeeConfig>(&info) else {
+        return Ok(MintFee::default());
+    };
+    let fee = config.get_epoch_fee(Clock::get()?.epoch);
+
+    Ok(MintFee {
+        basis_points: fee.transfer_fee_basis_points.into(),
+        maximum_fee: fee.maximum_fee.into(),
+    })
+}
+
+/// Amount to send so the recipient nets `net_amount` after the fee.
+pub fn gross_amount(fee: &MintFee, net_amount: u64) -> Result<u64> {
+    let fee = TransferFee {
+        epoch: 0.into(),
+        maximum_fee: fee.maximum_fee.into(),
+        transfer_fee_basis_points: fee.basis_points.into(),
+    };
+
+    Ok(fee
+        .calculate_pre_fee_amount(net_amount)
+        .ok_or(EscrowError::InvalidAmount)?)
+}
+
+/// Fees are quoted against the config seen at make, so a fee authority can't
+/// raise them under a live offer.
+pub fn check_fee_unchanged(mint: &InterfaceAccount<Mint>, expected: &MintFee) -> Result<()> {
+    require!(
+        mint_fee(mint)? == *expected,
+        EscrowError::TransferFeeChanged
+    );
+
+    Ok(())
+}
+
+/// Token-2022 won't close an account still holding withheld transfer fees, so
+/// they're swept to the mint first, where the fee authority collects them as
+/// usual. Harvesting needs no signer, and does nothing for mints without fees.
+pub fn harvest_withheld_fees<'info>(
+    token_program: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    vault: AccountInfo<'info>,
+) -> Result<()> {
+    if *mint.owner != spl_token_2022::ID
+        || get_mint_extension_data::<TransferFeeConfig>(&mint).is_err()
+    {
+        return Ok(());
+    }
+
+    harvest_withheld_tokens_to_mint(
+        CpiContext::new(
+            token_program.clone(),
+            HarvestWithheldTokensToMint {
+                token_program_id: token_program,
+                mint,
+            },
+        ),
+        vec![vault],
+    )
+}
+
+/// Basket legs can mix legacy and Token-2022 mints, so each leg names its own
+/// token program, which has to be the one owning the leg's mint.
+pub fn check_leg_token_program<'info>(
+    mint: &AccountInfo<'info>,
+    token_program: &'info AccountInfo<'info>,
+) -> Result<()> {
+    let token_program = Interface::<TokenInterface>::try_from(token_program)?;
+    require_keys_eq!(
+        *mint.owner,
+        token_program.key(),
+        EscrowError::InvalidLegAccounts
+    );
+
+    Ok(())
+}
+
+/// Checks `token_account` is `wallet`'s associated token account for `mint`
+/// and creates it if needed. Basket legs come in through `remaining_accounts`,
+/// where `init_if_needed` isn't available.
+pub fn init_associated_token_account<'info>(
+    payer: AccountInfo<'info>,
+    token_account: AccountInfo<'info>,
+    wallet: AccountInfo<'info>,
+    mint: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    associated_token_program: AccountInfo<'info>,
+) -> Result<()> {
+    require_keys_eq!(
+        token_account.key(),
+        get_associated_token_address_with_program_id(wallet.key, mint.key, token_program.key),
+        EscrowError::InvalidLegAccounts
+    );
+
+    create_idempotent(CpiContext::new(
+        associated_token_program,
+        Create {
+            payer,
+            associated_token: token_account,
+            authority: wallet,
+            mint,
+            system_program,
+            token_program,
+        },
+    ))?;
+
+    Ok(())
+}
+
+/// Moves everything in one of the basket's vaults to `destination` and closes
+/// the vault, rent goes back to the maker. With a `treasury_ata` the protocol
+/// fee is skimmed off first. `hook_accounts` are forwarded to every transfer.
+#[allow(clippy::too_many_arguments)]
+pub fn drain_basket_vault<'info>(
+    basket: &Account<'info, BasketEscrow>,
+    maker: AccountInfo<'info>,
+    mint: &'info AccountInfo<'info>,
+    vault: &'info AccountInfo<'info>,
+    destination: AccountInfo<'info>,
+    treasury_ata: Option<AccountInfo<'info>>,
+    token_program: AccountInfo<'info>,
+    hook_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    require_keys_eq!(
+        vault.key(),
+        get_associated_token_address_with_program_id(&basket.key(), mint.key, token_program.key),
+        EscrowError::InvalidLegAccounts
+    );
+
+    let decimals = InterfaceAccount::<Mint>::try_from(mint)?.decimals;
+    let mut amount = InterfaceAccount::<TokenAccount>::try_from(vault)?.amount;
+    let seeds: &[&[&[u8]]] = &[&[
+        "basket".as_bytes(),
+        basket.maker.as_ref(),
+        &basket.seed.to_le_bytes(),
+        &[basket.bump],
+    ]];
+
+    let transfer_from_vault = |to: AccountInfo<'info>, amount: u64| {
+        transfer_checked_with_hook(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                TransferChecked {
+                    from: vault.clone(),
+                    to,
+                    authority: basket.to_account_info(),
+                    mint: mint.clone(),
+                },
+                seeds,
+            )
+            .with_remaining_accounts(hook_accounts.to_vec()),
+            amount,
+            decimals,
+        )
+    };
+
+    if let Some(treasury_ata) = treasury_ata {
+        let fee = protocol_fee(amount, basket.protocol_fee_bps);
+
+        if fee > 0 {
+            transfer_from_vault(treasury_ata, fee)?;
+            amount -= fee;
+        }
+    }
+
+    transfer_from_vault(destination, amount)?;
+
+    harvest_withheld_fees(token_program.clone(), mint.clone(), vault.clone())?;
+
+    close_account(CpiContext::new_with_signer(
+        token_program.clone(),
+        CloseAccount {
+            account: vault.clone(),
+            destination: maker,
+            authority: basket.to_account_info(),
+        },
+        seeds,
+    ))?;
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::EscrowError, program::AnchorEscrow, state::Config};
+
+#[derive(Accounts)]
+pub struct InitConfig<'info> {
+    // other accounts
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Config::INIT_SPACE + Config::DISCRIMINATOR.len(),
+        seeds = ["config".as_bytes()],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    // only the upgrade authority can claim the config, otherwise whoever lands
+    // the first transaction after deploy would own the protocol fees
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, AnchorEscrow>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ EscrowError::InvalidAdmin
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    // programs
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitConfig<'info> {
+    pub fn populate_config(&mut self, treasury: Pubkey, fee_bps: u16, bump: u8) -> Result<()> {
+        self.config.set_inner(Config {
+            admin: self.admin.key(),
+            treasury,
+            fee_bps,
+            bump,
+        });
+
+        Ok(())
+    }
+}
+
+pub fn handler(ctx: Context<InitConfig>, treasury: Pubkey, fee_bps: u16) -> Result<()> {
+    Config::check_fee_bps(fee_bps)?;
+
+    ctx.accounts
+        .populate_config(treasury, fee_bps, ctx.bumps.config)?;
+
+    Ok(())
+}
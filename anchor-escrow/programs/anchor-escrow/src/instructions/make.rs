@@ -1,10 +1,17 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
-use crate::{errors::EscrowError, state::Escrow};
+use crate::{
+    errors::EscrowError,
+    instructions::{mint_fee, transfer_checked_with_hook},
+    state::{Config, Escrow},
+};
 
 #[derive(Accounts)]
 #[instruction(seed: u64)]
@@ -22,6 +29,12 @@ pub struct Make<'info> {
     )]
     pub escrow: Account<'info, Escrow>,
 
+    // required, makes fail until init_config has run after deploy. Falling
+    // back to no fee when it's missing would let makers skip the fee by
+    // leaving it out
+    #[account(seeds = ["config".as_bytes()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
     // token accounts
     #[account(mint::token_program = token_program)]
     pub mint_a: InterfaceAccount<'info, Mint>,
@@ -53,21 +66,52 @@ pub struct Make<'info> {
 }
 
 impl<'info> Make<'info> {
-    pub fn populate_escrow(&mut self, seed: u64, amount: u64, bump: u8) -> Result<()> {
+    pub fn populate_escrow(
+        &mut self,
+        seed: u64,
+        amount: u64,
+        taker: Option<Pubkey>,
+        expires_at: Option<i64>,
+        crank_tip: u64,
+        bump: u8,
+    ) -> Result<()> {
         self.escrow.set_inner(Escrow {
             seed,
             maker: self.maker.key(),
             mint_a: self.mint_a.key(),
             mint_b: self.mint_b.key(),
             recieve: amount,
+            deposited: 0,
+            fee_a: mint_fee(&self.mint_a)?,
+            fee_b: mint_fee(&self.mint_b)?,
+            taker,
+            expires_at,
+            crank_tip,
+            protocol_fee_bps: self.config.fee_bps,
             bump,
         });
 
         Ok(())
     }
 
-    pub fn deposit(&self, amount: u64) -> Result<()> {
-        transfer_checked(
+    // parked on top of the escrow rent, paid to whoever cranks it after expiry
+    pub fn fund_crank_tip(&self, crank_tip: u64) -> Result<()> {
+        transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.maker.to_account_info(),
+                    to: self.escrow.to_account_info(),
+                },
+            ),
+            crank_tip,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn deposit(&self, amount: u64, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        transfer_checked_with_hook(
             CpiContext::new(
                 self.token_program.to_account_info(),
                 TransferChecked {
@@ -76,23 +120,61 @@ impl<'info> Make<'info> {
                     authority: self.maker.to_account_info(),
                     mint: self.mint_a.to_account_info(),
                 },
-            ),
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec()),
             amount,
             self.mint_a.decimals,
         )?;
 
         Ok(())
     }
+
+    pub fn record_deposit(&mut self) -> Result<()> {
+        self.vault.reload()?;
+        self.escrow.deposited = self.vault.amount;
+
+        Ok(())
+    }
 }
 
-pub fn handler(ctx: Context<Make>, seed: u64, recieve: u64, amount: u64) -> Result<()> {
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, Make<'info>>,
+    seed: u64,
+    recieve: u64,
+    amount: u64,
+    taker: Option<Pubkey>,
+    expires_at: Option<i64>,
+    crank_tip: u64,
+) -> Result<()> {
     require!(recieve > 0, EscrowError::InvalidAmount);
     require!(amount > 0, EscrowError::InvalidAmount);
 
-    ctx.accounts
-        .populate_escrow(seed, recieve, ctx.bumps.escrow)?;
+    if let Some(expires_at) = expires_at {
+        require_gt!(
+            expires_at,
+            Clock::get()?.unix_timestamp,
+            EscrowError::InvalidExpiry
+        );
+    } else {
+        // nobody can crank an offer that never expires
+        require_eq!(crank_tip, 0, EscrowError::InvalidExpiry);
+    }
+
+    ctx.accounts.populate_escrow(
+        seed,
+        recieve,
+        taker,
+        expires_at,
+        crank_tip,
+        ctx.bumps.escrow,
+    )?;
+
+    if crank_tip > 0 {
+        ctx.accounts.fund_crank_tip(crank_tip)?;
+    }
 
-    ctx.accounts.deposit(amount)?;
+    ctx.accounts.deposit(amount, ctx.remaining_accounts)?;
+    ctx.accounts.record_deposit()?;
 
     Ok(())
 }
@@ -0,0 +1,153 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TransferChecked},
+};
+
+use crate::{
+    errors::EscrowError,
+    instructions::{
+        check_leg_token_program, init_associated_token_account, mint_fee,
+        transfer_checked_with_hook,
+    },
+    state::{BasketEscrow, BasketLeg, Config, ReceiveLeg},
+};
+
+#[derive(Accounts)]
+#[instruction(seed: u64, offered: Vec<BasketLeg>, receive: Vec<BasketLeg>)]
+pub struct MakeBasket<'info> {
+    // other accounts
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        init,
+        payer = maker,
+        space = BasketEscrow::space(offered.len(), receive.len()),
+        seeds = ["basket".as_bytes(), maker.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub basket: Account<'info, BasketEscrow>,
+
+    // required, makes fail until init_config has run after deploy. Falling
+    // back to no fee when it's missing would let makers skip the fee by
+    // leaving it out
+    #[account(seeds = ["config".as_bytes()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    // programs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MakeBasket<'info> {
+    /// `receive_mints` holds the mint of every leg the maker asks for, in
+    /// order, their transfer fees are fixed here for the take.
+    pub fn populate_basket(
+        &mut self,
+        seed: u64,
+        offered: &[BasketLeg],
+        receive: &[BasketLeg],
+        receive_mints: &'info [AccountInfo<'info>],
+        bump: u8,
+    ) -> Result<()> {
+        let receive = receive
+            .iter()
+            .zip(receive_mints)
+            .map(|(leg, mint)| {
+                require_keys_eq!(mint.key(), leg.mint, EscrowError::InvalidLegAccounts);
+
+                Ok(ReceiveLeg {
+                    mint: leg.mint,
+                    amount: leg.amount,
+                    fee: mint_fee(&InterfaceAccount::<Mint>::try_from(mint)?)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        self.basket.set_inner(BasketEscrow {
+            seed,
+            maker: self.maker.key(),
+            offered: offered.iter().map(|leg| leg.mint).collect(),
+            receive,
+            protocol_fee_bps: self.config.fee_bps,
+            bump,
+        });
+
+        Ok(())
+    }
+
+    /// `legs` holds `[mint, token program, maker token account, vault]` for
+    /// every offered leg, in order.
+    pub fn deposit_legs(
+        &self,
+        offered: &[BasketLeg],
+        legs: &'info [AccountInfo<'info>],
+        hook_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        for (leg, accounts) in offered.iter().zip(legs.chunks_exact(4)) {
+            let [mint, token_program, maker_ata, vault] = accounts else {
+                return err!(EscrowError::InvalidLegAccounts);
+            };
+
+            require_keys_eq!(mint.key(), leg.mint, EscrowError::InvalidLegAccounts);
+            check_leg_token_program(mint, token_program)?;
+            let decimals = InterfaceAccount::<Mint>::try_from(mint)?.decimals;
+
+            init_associated_token_account(
+                self.maker.to_account_info(),
+                vault.clone(),
+                self.basket.to_account_info(),
+                mint.clone(),
+                self.system_program.to_account_info(),
+                token_program.clone(),
+                self.associated_token_program.to_account_info(),
+            )?;
+
+            transfer_checked_with_hook(
+                CpiContext::new(
+                    token_program.clone(),
+                    TransferChecked {
+                        from: maker_ata.clone(),
+                        to: vault.clone(),
+                        authority: self.maker.to_account_info(),
+                        mint: mint.clone(),
+                    },
+                )
+                .with_remaining_accounts(hook_accounts.to_vec()),
+                leg.amount,
+                decimals,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Leg accounts come first, offered then the receive mints, followed by the
+/// extra accounts of any transfer hooks.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, MakeBasket<'info>>,
+    seed: u64,
+    offered: Vec<BasketLeg>,
+    receive: Vec<BasketLeg>,
+) -> Result<()> {
+    BasketEscrow::check_legs(&offered, &receive)?;
+
+    let offered_accounts = offered.len() * 4;
+    require_gte!(
+        ctx.remaining_accounts.len(),
+        offered_accounts + receive.len(),
+        EscrowError::InvalidLegAccounts
+    );
+
+    let (legs, rest) = ctx.remaining_accounts.split_at(offered_accounts);
+    let (receive_mints, hook_accounts) = rest.split_at(receive.len());
+
+    ctx.accounts
+        .populate_basket(seed, &offered, &receive, receive_mints, ctx.bumps.basket)?;
+
+    ctx.accounts.deposit_legs(&offered, legs, hook_accounts)?;
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    errors::EscrowError,
+    instructions::mint_fee,
+    state::{Escrow, LegacyEscrow},
+};
+
+#[derive(Accounts)]
+pub struct MigrateEscrow<'info> {
+    // other accounts
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: a `LegacyEscrow`, deserialized and checked against its seeds by
+    /// hand since it gets rewritten as an `Escrow` in place
+    #[account(mut, owner = crate::ID)]
+    pub escrow: UncheckedAccount<'info>,
+
+    // token accounts
+    #[account(mint::token_program = token_program)]
+    pub mint_a: InterfaceAccount<'info, Mint>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        associated_token::mint = mint_a,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    // programs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateEscrow<'info> {
+    pub fn load_legacy(&self) -> Result<LegacyEscrow> {
+        let legacy = LegacyEscrow::try_deserialize(&mut &self.escrow.try_borrow_data()?[..])?;
+
+        let expected = Pubkey::create_program_address(
+            &[
+                "escrow".as_bytes(),
+                legacy.maker.as_ref(),
+                legacy.seed.to_le_bytes().as_ref(),
+                &[legacy.bump],
+            ],
+            &crate::ID,
+        )
+        .map_err(|_| EscrowError::InvalidMaker)?;
+
+        require_keys_eq!(self.escrow.key(), expected, EscrowError::InvalidMaker);
+        require_keys_eq!(legacy.mint_a, self.mint_a.key(), EscrowError::InvalidMintA);
+        require_keys_eq!(legacy.mint_b, self.mint_b.key(), EscrowError::InvalidMintB);
+
+        Ok(legacy)
+    }
+
+    pub fn resize_escrow(&self) -> Result<()> {
+        let space = Escrow::INIT_SPACE + Escrow::DISCRIMINATOR.len();
+        let rent = Rent::get()?
+            .minimum_balance(space)
+            .saturating_sub(self.escrow.lamports());
+
+        if rent > 0 {
+            transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    Transfer {
+                        from: self.payer.to_account_info(),
+                        to: self.escrow.to_account_info(),
+                    },
+                ),
+                rent,
+            )?;
+        }
+
+        self.escrow.realloc(space, false)?;
+
+        Ok(())
+    }
+
+    /// Fills in the terms old offers behaved with: open to anyone, no expiry,
+    /// no protocol fee. Transfer fees are quoted as of now.
+    pub fn write_escrow(&self, legacy: LegacyEscrow) -> Result<()> {
+        let escrow = Escrow {
+            seed: legacy.seed,
+            maker: legacy.maker,
+            mint_a: legacy.mint_a,
+            mint_b: legacy.mint_b,
+            recieve: legacy.recieve,
+            deposited: self.vault.amount,
+            fee_a: mint_fee(&self.mint_a)?,
+            fee_b: mint_fee(&self.mint_b)?,
+            taker: None,
+            expires_at: None,
+            crank_tip: 0,
+            protocol_fee_bps: 0,
+            bump: legacy.bump,
+        };
+
+        escrow.try_serialize(&mut &mut self.escrow.try_borrow_mut_data()?[..])?;
+
+        Ok(())
+    }
+}
+
+/// Anyone can migrate, nothing about the offer changes hands.
+pub fn handler(ctx: Context<MigrateEscrow>) -> Result<()> {
+    let legacy = ctx.accounts.load_legacy()?;
+
+    ctx.accounts.resize_escrow()?;
+    ctx.accounts.write_escrow(legacy)?;
+
+    Ok(())
+}
@@ -4,3 +4,23 @@ pub mod take;
 pub use take::*;
 pub mod refund;
 pub use refund::*;
+pub mod take_partial;
+pub use take_partial::*;
+pub mod crank_expired;
+pub use crank_expired::*;
+pub mod helpers;
+pub use helpers::*;
+pub mod amend;
+pub use amend::*;
+pub mod make_basket;
+pub use make_basket::*;
+pub mod take_basket;
+pub use take_basket::*;
+pub mod refund_basket;
+pub use refund_basket::*;
+pub mod init_config;
+pub use init_config::*;
+pub mod update_config;
+pub use update_config::*;
+pub mod migrate_escrow;
+pub use migrate_escrow::*;
@@ -2,12 +2,15 @@ use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
     token_interface::{
-        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
-        TransferChecked,
+        close_account, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
     },
 };
 
-use crate::{errors::EscrowError, state::Escrow};
+use crate::{
+    errors::EscrowError,
+    instructions::{harvest_withheld_fees, transfer_checked_with_hook},
+    state::Escrow,
+};
 
 #[derive(Accounts)]
 pub struct Refund<'info> {
@@ -27,6 +30,7 @@ pub struct Refund<'info> {
 
     // token accounts
     #[account(
+        mut,
         mint::token_program = token_program
     )]
     pub mint_a: InterfaceAccount<'info, Mint>,
@@ -55,8 +59,11 @@ pub struct Refund<'info> {
 }
 
 impl<'info> Refund<'info> {
-    pub fn transfer_to_maker_and_close_vault(&self) -> Result<()> {
-        transfer_checked(
+    pub fn transfer_to_maker_and_close_vault(
+        &self,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        transfer_checked_with_hook(
             CpiContext::new_with_signer(
                 self.token_program.to_account_info(),
                 TransferChecked {
@@ -71,11 +78,18 @@ impl<'info> Refund<'info> {
                     self.escrow.seed.to_le_bytes().as_ref(),
                     &[self.escrow.bump],
                 ]],
-            ),
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec()),
             self.vault.amount,
             self.mint_a.decimals,
         )?;
 
+        harvest_withheld_fees(
+            self.token_program.to_account_info(),
+            self.mint_a.to_account_info(),
+            self.vault.to_account_info(),
+        )?;
+
         close_account(CpiContext::new_with_signer(
             self.token_program.to_account_info(),
             CloseAccount {
@@ -95,8 +109,9 @@ impl<'info> Refund<'info> {
     }
 }
 
-pub fn handler(ctx: Context<Refund>) -> Result<()> {
-    ctx.accounts.transfer_to_maker_and_close_vault()?;
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, Refund<'info>>) -> Result<()> {
+    ctx.accounts
+        .transfer_to_maker_and_close_vault(ctx.remaining_accounts)?;
 
     Ok(())
 }
@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+
+use crate::{
+    errors::EscrowError,
+    instructions::{check_leg_token_program, drain_basket_vault, init_associated_token_account},
+    state::BasketEscrow,
+};
+
+#[derive(Accounts)]
+pub struct RefundBasket<'info> {
+    // other accounts
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        mut,
+        close = maker,
+        seeds = ["basket".as_bytes(), maker.key().as_ref(), basket.seed.to_le_bytes().as_ref()],
+        bump = basket.bump,
+        has_one = maker @ EscrowError::InvalidMaker,
+    )]
+    pub basket: Account<'info, BasketEscrow>,
+
+    // programs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RefundBasket<'info> {
+    /// `[mint, token program, vault, maker token account]` for every offered
+    /// mint, in order, followed by the extra accounts of any transfer hooks.
+    /// Mints are writable, withheld fees get harvested to them before the
+    /// vaults close.
+    pub fn transfer_to_maker_and_close_vaults(
+        &self,
+        remaining_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        let leg_accounts = self.basket.offered.len() * 4;
+        require_gte!(
+            remaining_accounts.len(),
+            leg_accounts,
+            EscrowError::InvalidLegAccounts
+        );
+
+        let (legs, hook_accounts) = remaining_accounts.split_at(leg_accounts);
+
+        for (mint_key, accounts) in self.basket.offered.iter().zip(legs.chunks_exact(4)) {
+            let [mint, token_program, vault, maker_ata] = accounts else {
+                return err!(EscrowError::InvalidLegAccounts);
+            };
+
+            require_keys_eq!(mint.key(), *mint_key, EscrowError::InvalidLegAccounts);
+            check_leg_token_program(mint, token_program)?;
+
+            init_associated_token_account(
+                self.maker.to_account_info(),
+                maker_ata.clone(),
+                self.maker.to_account_info(),
+                mint.clone(),
+                self.system_program.to_account_info(),
+                token_program.clone(),
+                self.associated_token_program.to_account_info(),
+            )?;
+
+            drain_basket_vault(
+                &self.basket,
+                self.maker.to_account_info(),
+                mint,
+                vault,
+                maker_ata.clone(),
+                None,
+                token_program.clone(),
+                hook_accounts,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, RefundBasket<'info>>) -> Result<()> {
+    ctx.accounts
+        .transfer_to_maker_and_close_vaults(ctx.remaining_accounts)?;
+
+    Ok(())
+}
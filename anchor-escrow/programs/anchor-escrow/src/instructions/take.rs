@@ -1,13 +1,19 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    associated_token::AssociatedToken,
+    associated_token::{get_associated_token_address_with_program_id, AssociatedToken},
     token_interface::{
-        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
-        TransferChecked,
+        close_account, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
     },
 };
 
-use crate::{errors::EscrowError, state::Escrow};
+use crate::{
+    errors::EscrowError,
+    instructions::{
+        check_fee_unchanged, gross_amount, harvest_withheld_fees, init_associated_token_account,
+        transfer_checked_with_hook,
+    },
+    state::{protocol_fee, Config, Escrow},
+};
 
 #[derive(Accounts)]
 pub struct Take<'info> {
@@ -27,11 +33,16 @@ pub struct Take<'info> {
         has_one = maker @ EscrowError::InvalidMaker,
         has_one = mint_a @ EscrowError::InvalidMintA,
         has_one = mint_b @ EscrowError::InvalidMintB,
+        constraint = escrow.is_taker_allowed(&taker.key()) @ EscrowError::InvalidTaker,
     )]
     pub escrow: Account<'info, Escrow>,
 
+    #[account(seeds = ["config".as_bytes()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
     // token accounts
     #[account(
+        mut,
         mint::token_program = token_program
     )]
     pub mint_a: InterfaceAccount<'info, Mint>,
@@ -75,6 +86,15 @@ pub struct Take<'info> {
     )]
     pub maker_ata_token_b: InterfaceAccount<'info, TokenAccount>,
 
+    /// CHECK: only owns the token account fees are paid into, pinned by config
+    #[account(address = config.treasury @ EscrowError::InvalidTreasury)]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// CHECK: the treasury's token account for mint a, checked and created
+    /// in `transfer_to_treasury`. Only needed when a protocol fee is owed
+    #[account(mut)]
+    pub treasury_ata_token_a: Option<UncheckedAccount<'info>>,
+
     // programs
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Interface<'info, TokenInterface>,
@@ -82,8 +102,30 @@ pub struct Take<'info> {
 }
 
 impl<'info> Take<'info> {
-    pub fn transfer_to_maker(&self) -> Result<()> {
-        transfer_checked(
+    /// Guards the taker against an amend landing between quote and fill. Checks
+    /// the deposit the maker set rather than the vault balance, which anyone
+    /// can bump by sending dust to the vault.
+    pub fn check_terms(&self, expected_receive: u64, expected_amount_a: u64) -> Result<()> {
+        require!(
+            self.escrow.recieve == expected_receive && self.escrow.deposited == expected_amount_a,
+            EscrowError::OfferChanged
+        );
+
+        Ok(())
+    }
+
+    pub fn check_fees_unchanged(&self) -> Result<()> {
+        check_fee_unchanged(&self.mint_a, &self.escrow.fee_a)?;
+        check_fee_unchanged(&self.mint_b, &self.escrow.fee_b)?;
+
+        Ok(())
+    }
+
+    pub fn transfer_to_maker(&self, remaining_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        // gross up so the maker nets exactly what they asked for
+        let amount_b = gross_amount(&self.escrow.fee_b, self.escrow.recieve)?;
+
+        transfer_checked_with_hook(
             CpiContext::new(
                 self.token_program.to_account_info(),
                 TransferChecked {
@@ -92,16 +134,72 @@ impl<'info> Take<'info> {
                     authority: self.taker.to_account_info(),
                     mint: self.mint_b.to_account_info(),
                 },
-            ),
-            self.escrow.recieve,
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec()),
+            amount_b,
             self.mint_b.decimals,
         )?;
 
         Ok(())
     }
 
-    pub fn transfer_to_taker_and_close_vault(&self) -> Result<()> {
-        transfer_checked(
+    pub fn transfer_to_treasury(
+        &self,
+        amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let Some(treasury_ata) = &self.treasury_ata_token_a else {
+            return err!(EscrowError::InvalidTreasury);
+        };
+        require_keys_eq!(
+            treasury_ata.key(),
+            get_associated_token_address_with_program_id(
+                &self.treasury.key(),
+                &self.mint_a.key(),
+                &self.token_program.key(),
+            ),
+            EscrowError::InvalidTreasury
+        );
+
+        init_associated_token_account(
+            self.taker.to_account_info(),
+            treasury_ata.to_account_info(),
+            self.treasury.to_account_info(),
+            self.mint_a.to_account_info(),
+            self.system_program.to_account_info(),
+            self.token_program.to_account_info(),
+            self.associated_token_program.to_account_info(),
+        )?;
+
+        transfer_checked_with_hook(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.vault.to_account_info(),
+                    to: treasury_ata.to_account_info(),
+                    authority: self.escrow.to_account_info(),
+                    mint: self.mint_a.to_account_info(),
+                },
+                &[&[
+                    "escrow".as_bytes(),
+                    self.maker.key().as_ref(),
+                    self.escrow.seed.to_le_bytes().as_ref(),
+                    &[self.escrow.bump],
+                ]],
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec()),
+            amount,
+            self.mint_a.decimals,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn transfer_to_taker_and_close_vault(
+        &self,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        transfer_checked_with_hook(
             CpiContext::new_with_signer(
                 self.token_program.to_account_info(),
                 TransferChecked {
@@ -116,11 +214,18 @@ impl<'info> Take<'info> {
                     self.escrow.seed.to_le_bytes().as_ref(),
                     &[self.escrow.bump],
                 ]],
-            ),
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec()),
             self.vault.amount,
             self.mint_a.decimals,
         )?;
 
+        harvest_withheld_fees(
+            self.token_program.to_account_info(),
+            self.mint_a.to_account_info(),
+            self.vault.to_account_info(),
+        )?;
+
         close_account(CpiContext::new_with_signer(
             self.token_program.to_account_info(),
             CloseAccount {
@@ -140,9 +245,30 @@ impl<'info> Take<'info> {
     }
 }
 
-pub fn handler(ctx: Context<Take>) -> Result<()> {
-    ctx.accounts.transfer_to_maker()?;
-    ctx.accounts.transfer_to_taker_and_close_vault()?;
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, Take<'info>>,
+    expected_receive: u64,
+    expected_amount_a: u64,
+) -> Result<()> {
+    ctx.accounts.escrow.check_not_expired()?;
+    ctx.accounts
+        .check_terms(expected_receive, expected_amount_a)?;
+    ctx.accounts.check_fees_unchanged()?;
+
+    ctx.accounts.transfer_to_maker(ctx.remaining_accounts)?;
+
+    let fee = protocol_fee(
+        ctx.accounts.vault.amount,
+        ctx.accounts.escrow.protocol_fee_bps,
+    );
+    if fee > 0 {
+        ctx.accounts
+            .transfer_to_treasury(fee, ctx.remaining_accounts)?;
+        ctx.accounts.vault.reload()?;
+    }
+
+    ctx.accounts
+        .transfer_to_taker_and_close_vault(ctx.remaining_accounts)?;
 
     Ok(())
 }
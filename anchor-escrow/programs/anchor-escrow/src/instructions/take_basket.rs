@@ -0,0 +1,178 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TransferChecked},
+};
+
+use crate::{
+    errors::EscrowError,
+    instructions::{
+        check_fee_unchanged, check_leg_token_program, drain_basket_vault, gross_amount,
+        init_associated_token_account, transfer_checked_with_hook,
+    },
+    state::{protocol_fee, BasketEscrow, Config},
+};
+
+#[derive(Accounts)]
+pub struct TakeBasket<'info> {
+    // other accounts
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = maker, // send rent back to maker
+        seeds = ["basket".as_bytes(), maker.key().as_ref(), basket.seed.to_le_bytes().as_ref()],
+        bump = basket.bump,
+        has_one = maker @ EscrowError::InvalidMaker,
+    )]
+    pub basket: Account<'info, BasketEscrow>,
+
+    #[account(seeds = ["config".as_bytes()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: only owns the token accounts fees are paid into, pinned by config
+    #[account(address = config.treasury @ EscrowError::InvalidTreasury)]
+    pub treasury: UncheckedAccount<'info>,
+
+    // programs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> TakeBasket<'info> {
+    /// `[mint, token program, vault, taker token account, treasury token
+    /// account]` for every offered mint, in order. Mints are writable,
+    /// withheld fees get harvested to them before the vaults close. The
+    /// treasury token account is only created for legs that owe a fee.
+    pub fn transfer_offered_to_taker(
+        &self,
+        legs: &'info [AccountInfo<'info>],
+        hook_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        for (mint_key, accounts) in self.basket.offered.iter().zip(legs.chunks_exact(5)) {
+            let [mint, token_program, vault, taker_ata, treasury_ata] = accounts else {
+                return err!(EscrowError::InvalidLegAccounts);
+            };
+
+            require_keys_eq!(mint.key(), *mint_key, EscrowError::InvalidLegAccounts);
+            check_leg_token_program(mint, token_program)?;
+
+            init_associated_token_account(
+                self.taker.to_account_info(),
+                taker_ata.clone(),
+                self.taker.to_account_info(),
+                mint.clone(),
+                self.system_program.to_account_info(),
+                token_program.clone(),
+                self.associated_token_program.to_account_info(),
+            )?;
+
+            // drain_basket_vault checks the vault address
+            let amount = InterfaceAccount::<TokenAccount>::try_from(vault)?.amount;
+            let treasury_ata = if protocol_fee(amount, self.basket.protocol_fee_bps) > 0 {
+                init_associated_token_account(
+                    self.taker.to_account_info(),
+                    treasury_ata.clone(),
+                    self.treasury.to_account_info(),
+                    mint.clone(),
+                    self.system_program.to_account_info(),
+                    token_program.clone(),
+                    self.associated_token_program.to_account_info(),
+                )?;
+                Some(treasury_ata.clone())
+            } else {
+                None
+            };
+
+            drain_basket_vault(
+                &self.basket,
+                self.maker.to_account_info(),
+                mint,
+                vault,
+                taker_ata.clone(),
+                treasury_ata,
+                token_program.clone(),
+                hook_accounts,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// `[mint, token program, taker token account, maker token account]` for
+    /// every mint the maker asked for, in order. Grossed up by the transfer
+    /// fee seen at make, which has to still hold, so the maker nets each
+    /// leg's amount.
+    pub fn transfer_receive_to_maker(
+        &self,
+        legs: &'info [AccountInfo<'info>],
+        hook_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        for (leg, accounts) in self.basket.receive.iter().zip(legs.chunks_exact(4)) {
+            let [mint, token_program, taker_ata, maker_ata] = accounts else {
+                return err!(EscrowError::InvalidLegAccounts);
+            };
+
+            require_keys_eq!(mint.key(), leg.mint, EscrowError::InvalidLegAccounts);
+            check_leg_token_program(mint, token_program)?;
+            let mint_account = InterfaceAccount::<Mint>::try_from(mint)?;
+            check_fee_unchanged(&mint_account, &leg.fee)?;
+            let amount = gross_amount(&leg.fee, leg.amount)?;
+
+            init_associated_token_account(
+                self.taker.to_account_info(),
+                maker_ata.clone(),
+                self.maker.to_account_info(),
+                mint.clone(),
+                self.system_program.to_account_info(),
+                token_program.clone(),
+                self.associated_token_program.to_account_info(),
+            )?;
+
+            transfer_checked_with_hook(
+                CpiContext::new(
+                    token_program.clone(),
+                    TransferChecked {
+                        from: taker_ata.clone(),
+                        to: maker_ata.clone(),
+                        authority: self.taker.to_account_info(),
+                        mint: mint.clone(),
+                    },
+                )
+                .with_remaining_accounts(hook_accounts.to_vec()),
+                amount,
+                mint_account.decimals,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Leg accounts come first, offered then receive, followed by the extra
+/// accounts of any transfer hooks, shared by every leg.
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, TakeBasket<'info>>) -> Result<()> {
+    let offered_accounts = ctx.accounts.basket.offered.len() * 5;
+    let receive_accounts = ctx.accounts.basket.receive.len() * 4;
+
+    // every leg settles here or the whole take fails
+    require_gte!(
+        ctx.remaining_accounts.len(),
+        offered_accounts + receive_accounts,
+        EscrowError::InvalidLegAccounts
+    );
+
+    let (offered, rest) = ctx.remaining_accounts.split_at(offered_accounts);
+    let (receive, hook_accounts) = rest.split_at(receive_accounts);
+
+    ctx.accounts
+        .transfer_receive_to_maker(receive, hook_accounts)?;
+    ctx.accounts
+        .transfer_offered_to_taker(offered, hook_accounts)?;
+
+    Ok(())
+}
@@ -0,0 +1,300 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{get_associated_token_address_with_program_id, AssociatedToken},
+    token_interface::{
+        close_account, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+    },
+};
+
+use crate::{
+    errors::EscrowError,
+    instructions::{
+        check_fee_unchanged, gross_amount, harvest_withheld_fees, init_associated_token_account,
+        transfer_checked_with_hook,
+    },
+    state::{protocol_fee, Config, Escrow},
+};
+
+#[derive(Accounts)]
+pub struct TakePartial<'info> {
+    // other accounts
+    #[account(mut)]
+    pub taker: Signer<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        // closed by hand once the last fill goes through
+        seeds = ["escrow".as_bytes(), maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+        // check keys with account state
+        has_one = maker @ EscrowError::InvalidMaker,
+        has_one = mint_a @ EscrowError::InvalidMintA,
+        has_one = mint_b @ EscrowError::InvalidMintB,
+        constraint = escrow.is_taker_allowed(&taker.key()) @ EscrowError::InvalidTaker,
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(seeds = ["config".as_bytes()], bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    // token accounts
+    #[account(
+        mut,
+        mint::token_program = token_program
+    )]
+    pub mint_a: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub mint_b: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = mint_a,
+        associated_token::authority = taker,
+        associated_token::token_program = token_program
+    )]
+    pub taker_ata_token_a: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = taker,
+        associated_token::token_program = token_program
+    )]
+    pub taker_ata_token_b: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = taker,
+        associated_token::mint = mint_b,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program
+    )]
+    pub maker_ata_token_b: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: only owns the token account fees are paid into, pinned by config
+    #[account(address = config.treasury @ EscrowError::InvalidTreasury)]
+    pub treasury: UncheckedAccount<'info>,
+
+    /// CHECK: the treasury's token account for mint a, checked and created
+    /// in `transfer_to_treasury`. Only needed when a protocol fee is owed
+    #[account(mut)]
+    pub treasury_ata_token_a: Option<UncheckedAccount<'info>>,
+
+    // programs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> TakePartial<'info> {
+    pub fn check_fees_unchanged(&self) -> Result<()> {
+        check_fee_unchanged(&self.mint_a, &self.escrow.fee_a)?;
+        check_fee_unchanged(&self.mint_b, &self.escrow.fee_b)?;
+
+        Ok(())
+    }
+
+    /// Share of the deposit paid out for `amount_b`, rounded down so the maker
+    /// never sells below the original price. Priced off what the maker put in,
+    /// not the vault balance, so tokens sent to the vault can't move it.
+    pub fn fill_amount(&self, amount_b: u64) -> Result<u64> {
+        require!(amount_b > 0, EscrowError::InvalidAmount);
+        require_gte!(self.escrow.recieve, amount_b, EscrowError::InvalidAmount);
+
+        if amount_b == self.escrow.recieve {
+            return Ok(self.vault.amount);
+        }
+
+        let amount_a = (amount_b as u128)
+            .checked_mul(self.escrow.deposited as u128)
+            .and_then(|n| n.checked_div(self.escrow.recieve as u128))
+            .ok_or(EscrowError::InvalidAmount)?;
+
+        require!(amount_a > 0, EscrowError::InvalidAmount);
+
+        Ok(amount_a as u64)
+    }
+
+    pub fn transfer_to_maker(
+        &self,
+        amount_b: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        // gross up so the maker nets exactly `amount_b`
+        let gross_b = gross_amount(&self.escrow.fee_b, amount_b)?;
+
+        transfer_checked_with_hook(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.taker_ata_token_b.to_account_info(),
+                    to: self.maker_ata_token_b.to_account_info(),
+                    authority: self.taker.to_account_info(),
+                    mint: self.mint_b.to_account_info(),
+                },
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec()),
+            gross_b,
+            self.mint_b.decimals,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn transfer_to_treasury(
+        &self,
+        amount: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let Some(treasury_ata) = &self.treasury_ata_token_a else {
+            return err!(EscrowError::InvalidTreasury);
+        };
+        require_keys_eq!(
+            treasury_ata.key(),
+            get_associated_token_address_with_program_id(
+                &self.treasury.key(),
+                &self.mint_a.key(),
+                &self.token_program.key(),
+            ),
+            EscrowError::InvalidTreasury
+        );
+
+        init_associated_token_account(
+            self.taker.to_account_info(),
+            treasury_ata.to_account_info(),
+            self.treasury.to_account_info(),
+            self.mint_a.to_account_info(),
+            self.system_program.to_account_info(),
+            self.token_program.to_account_info(),
+            self.associated_token_program.to_account_info(),
+        )?;
+
+        transfer_checked_with_hook(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.vault.to_account_info(),
+                    to: treasury_ata.to_account_info(),
+                    authority: self.escrow.to_account_info(),
+                    mint: self.mint_a.to_account_info(),
+                },
+                &[&[
+                    "escrow".as_bytes(),
+                    self.maker.key().as_ref(),
+                    self.escrow.seed.to_le_bytes().as_ref(),
+                    &[self.escrow.bump],
+                ]],
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec()),
+            amount,
+            self.mint_a.decimals,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn transfer_to_taker(
+        &self,
+        amount_a: u64,
+        remaining_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        transfer_checked_with_hook(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.vault.to_account_info(),
+                    to: self.taker_ata_token_a.to_account_info(),
+                    authority: self.escrow.to_account_info(),
+                    mint: self.mint_a.to_account_info(),
+                },
+                &[&[
+                    "escrow".as_bytes(),
+                    self.maker.key().as_ref(),
+                    self.escrow.seed.to_le_bytes().as_ref(),
+                    &[self.escrow.bump],
+                ]],
+            )
+            .with_remaining_accounts(remaining_accounts.to_vec()),
+            amount_a,
+            self.mint_a.decimals,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn close_vault_and_escrow(&self) -> Result<()> {
+        harvest_withheld_fees(
+            self.token_program.to_account_info(),
+            self.mint_a.to_account_info(),
+            self.vault.to_account_info(),
+        )?;
+
+        close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.vault.to_account_info(),
+                destination: self.maker.to_account_info(),
+                authority: self.escrow.to_account_info(),
+            },
+            &[&[
+                "escrow".as_bytes(),
+                self.maker.key().as_ref(),
+                self.escrow.seed.to_le_bytes().as_ref(),
+                &[self.escrow.bump],
+            ]],
+        ))?;
+
+        // send rent back to maker
+        self.escrow.close(self.maker.to_account_info())?;
+
+        Ok(())
+    }
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, TakePartial<'info>>,
+    amount_b: u64,
+    min_amount_a: u64,
+) -> Result<()> {
+    ctx.accounts.escrow.check_not_expired()?;
+    ctx.accounts.check_fees_unchanged()?;
+
+    let amount_a = ctx.accounts.fill_amount(amount_b)?;
+    let fee = protocol_fee(amount_a, ctx.accounts.escrow.protocol_fee_bps);
+
+    // other fills don't move the price, only an amend can
+    require_gte!(amount_a - fee, min_amount_a, EscrowError::OfferChanged);
+
+    ctx.accounts
+        .transfer_to_maker(amount_b, ctx.remaining_accounts)?;
+    if fee > 0 {
+        ctx.accounts
+            .transfer_to_treasury(fee, ctx.remaining_accounts)?;
+    }
+    ctx.accounts
+        .transfer_to_taker(amount_a - fee, ctx.remaining_accounts)?;
+
+    ctx.accounts.escrow.recieve -= amount_b;
+    ctx.accounts.escrow.deposited = ctx.accounts.escrow.deposited.saturating_sub(amount_a);
+
+    if ctx.accounts.escrow.recieve == 0 {
+        ctx.accounts.close_vault_and_escrow()?;
+    }
+
+    Ok(())
+}
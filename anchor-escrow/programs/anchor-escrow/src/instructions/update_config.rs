@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::EscrowError, state::Config};
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    // other accounts
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = ["config".as_bytes()],
+        bump = config.bump,
+        has_one = admin @ EscrowError::InvalidAdmin,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+pub fn handler(
+    ctx: Context<UpdateConfig>,
+    admin: Pubkey,
+    treasury: Pubkey,
+    fee_bps: u16,
+) -> Result<()> {
+    Config::check_fee_bps(fee_bps)?;
+
+    // open escrows keep the rate they were made with
+    let config = &mut ctx.accounts.config;
+    config.admin = admin;
+    config.treasury = treasury;
+    config.fee_bps = fee_bps;
+
+    Ok(())
+}
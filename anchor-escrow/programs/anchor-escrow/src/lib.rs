@@ -1,10 +1,11 @@
 use anchor_lang::prelude::*;
 
-mod errors;
+pub mod errors;
 mod instructions;
-mod state;
+pub mod state;
 
 use instructions::*;
+use state::BasketLeg;
 
 declare_id!("22222222222222222222222222222222222222222222");
 
@@ -13,17 +14,97 @@ pub mod anchor_escrow {
     use super::*;
 
     #[instruction(discriminator = 0)]
-    pub fn make(ctx: Context<Make>, seed: u64, recieve: u64, amount: u64) -> Result<()> {
-        instructions::make::handler(ctx, seed, recieve, amount)
+    pub fn make<'info>(
+        ctx: Context<'_, '_, '_, 'info, Make<'info>>,
+        seed: u64,
+        recieve: u64,
+        amount: u64,
+        taker: Option<Pubkey>,
+        expires_at: Option<i64>,
+        crank_tip: u64,
+    ) -> Result<()> {
+        instructions::make::handler(ctx, seed, recieve, amount, taker, expires_at, crank_tip)
     }
 
     #[instruction(discriminator = 1)]
-    pub fn take(ctx: Context<Take>) -> Result<()> {
-        instructions::take::handler(ctx)
+    pub fn take<'info>(
+        ctx: Context<'_, '_, '_, 'info, Take<'info>>,
+        expected_receive: u64,
+        expected_amount_a: u64,
+    ) -> Result<()> {
+        instructions::take::handler(ctx, expected_receive, expected_amount_a)
     }
 
     #[instruction(discriminator = 2)]
-    pub fn refund(ctx: Context<Refund>) -> Result<()> {
+    pub fn refund<'info>(ctx: Context<'_, '_, '_, 'info, Refund<'info>>) -> Result<()> {
         instructions::refund::handler(ctx)
     }
+
+    #[instruction(discriminator = 3)]
+    pub fn take_partial<'info>(
+        ctx: Context<'_, '_, '_, 'info, TakePartial<'info>>,
+        amount_b: u64,
+        min_amount_a: u64,
+    ) -> Result<()> {
+        instructions::take_partial::handler(ctx, amount_b, min_amount_a)
+    }
+
+    #[instruction(discriminator = 4)]
+    pub fn crank_expired<'info>(
+        ctx: Context<'_, '_, '_, 'info, CrankExpired<'info>>,
+    ) -> Result<()> {
+        instructions::crank_expired::handler(ctx)
+    }
+
+    #[instruction(discriminator = 5)]
+    pub fn amend<'info>(
+        ctx: Context<'_, '_, '_, 'info, Amend<'info>>,
+        recieve: u64,
+        deposit: u64,
+        withdraw: u64,
+    ) -> Result<()> {
+        instructions::amend::handler(ctx, recieve, deposit, withdraw)
+    }
+
+    #[instruction(discriminator = 6)]
+    pub fn make_basket<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MakeBasket<'info>>,
+        seed: u64,
+        offered: Vec<BasketLeg>,
+        receive: Vec<BasketLeg>,
+    ) -> Result<()> {
+        instructions::make_basket::handler(ctx, seed, offered, receive)
+    }
+
+    #[instruction(discriminator = 7)]
+    pub fn take_basket<'info>(ctx: Context<'_, '_, 'info, 'info, TakeBasket<'info>>) -> Result<()> {
+        instructions::take_basket::handler(ctx)
+    }
+
+    #[instruction(discriminator = 8)]
+    pub fn refund_basket<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RefundBasket<'info>>,
+    ) -> Result<()> {
+        instructions::refund_basket::handler(ctx)
+    }
+
+    #[instruction(discriminator = 9)]
+    pub fn init_config(ctx: Context<InitConfig>, treasury: Pubkey, fee_bps: u16) -> Result<()> {
+        instructions::init_config::handler(ctx, treasury, fee_bps)
+    }
+
+    #[instruction(discriminator = 10)]
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        admin: Pubkey,
+        treasury: Pubkey,
+        fee_bps: u16,
+    ) -> Result<()> {
+        instructions::update_config::handler(ctx, admin, treasury, fee_bps)
+    }
+
+    #[instruction(discriminator = 11)]
+    pub fn migrate_escrow(ctx: Context<MigrateEscrow>) -> Result<()> {
+        instructions::migrate_escrow::handler(ctx)
+    }
 }
@@ -1,12 +1,159 @@
 use anchor_lang::prelude::*;
 
+use crate::errors::EscrowError;
+
+// per side. a take carries 4 accounts per offered leg and 3 per received one
+// on top of its own, 3 a side still leaves room for hook accounts in a
+// transaction without lookup tables
+pub const MAX_BASKET_LEGS: usize = 3;
+pub const BASIS_POINTS: u16 = 10_000;
+pub const MAX_PROTOCOL_FEE_BPS: u16 = 1_000; // 10%
+
+/// Protocol wide settings, a single PDA owned by the admin.
+#[derive(InitSpace)]
+#[account(discriminator = 3)]
+pub struct Config {
+    pub admin: Pubkey,
+    // owner of the associated token accounts protocol fees are paid into
+    pub treasury: Pubkey,
+    pub fee_bps: u16,
+    pub bump: u8,
+}
+
+impl Config {
+    pub fn check_fee_bps(fee_bps: u16) -> Result<()> {
+        require_gte!(
+            MAX_PROTOCOL_FEE_BPS,
+            fee_bps,
+            EscrowError::InvalidProtocolFee
+        );
+
+        Ok(())
+    }
+}
+
+/// Share of `amount` skimmed for the treasury, rounded down.
+pub fn protocol_fee(amount: u64, fee_bps: u16) -> u64 {
+    (amount as u128 * fee_bps as u128 / BASIS_POINTS as u128) as u64
+}
+
+/// Token-2022 transfer fee of a mint, all zero when it has none.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace)]
+pub struct MintFee {
+    pub basis_points: u16,
+    pub maximum_fee: u64,
+}
+
+/// Escrows made before expiry, fees and the other per-offer terms were added.
+/// They keep this layout until `migrate_escrow` rewrites them as an `Escrow`.
 #[derive(InitSpace)]
 #[account(discriminator = 1)]
+pub struct LegacyEscrow {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub recieve: u64,
+    pub bump: u8,
+}
+
+#[derive(InitSpace)]
+#[account(discriminator = 4)]
 pub struct Escrow {
     pub seed: u64,
     pub maker: Pubkey,
     pub mint_a: Pubkey,
     pub mint_b: Pubkey,
     pub recieve: u64,
+    // net of mint a fees, what the vault actually received
+    pub deposited: u64,
+    pub fee_a: MintFee,
+    pub fee_b: MintFee,
+    pub taker: Option<Pubkey>,
+    pub expires_at: Option<i64>,
+    pub crank_tip: u64,
+    // config fee rate when the offer was made
+    pub protocol_fee_bps: u16,
+    pub bump: u8,
+}
+
+impl Escrow {
+    pub fn is_taker_allowed(&self, taker: &Pubkey) -> bool {
+        self.taker.is_none() || self.taker == Some(*taker)
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+
+    pub fn check_not_expired(&self) -> Result<()> {
+        require!(
+            !self.is_expired(Clock::get()?.unix_timestamp),
+            EscrowError::OfferExpired
+        );
+
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct BasketLeg {
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+/// A leg the maker asked for, with its mint's transfer fee seen at make so
+/// the fee authority can't raise it under a live basket.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct ReceiveLeg {
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub fee: MintFee,
+}
+
+/// Offers several mints for several others, every leg settles in the same
+/// take. Sized to its own legs at make rather than to `MAX_BASKET_LEGS`.
+#[account(discriminator = 2)]
+pub struct BasketEscrow {
+    pub seed: u64,
+    pub maker: Pubkey,
+    // each offered mint sits in the basket's own associated token account
+    pub offered: Vec<Pubkey>,
+    pub receive: Vec<ReceiveLeg>,
+    pub protocol_fee_bps: u16,
     pub bump: u8,
 }
+
+impl BasketEscrow {
+    pub fn space(offered: usize, receive: usize) -> usize {
+        Self::DISCRIMINATOR.len()
+            + 8
+            + 32
+            + 4
+            + offered * 32
+            + 4
+            + receive * ReceiveLeg::INIT_SPACE
+            + 2
+            + 1
+    }
+
+    pub fn check_legs(offered: &[BasketLeg], receive: &[BasketLeg]) -> Result<()> {
+        for legs in [offered, receive] {
+            require!(
+                !legs.is_empty() && legs.len() <= MAX_BASKET_LEGS,
+                EscrowError::InvalidBasket
+            );
+
+            for (i, leg) in legs.iter().enumerate() {
+                require!(leg.amount > 0, EscrowError::InvalidAmount);
+                // one vault per mint, so a mint can only show up once per side
+                require!(
+                    legs[..i].iter().all(|other| other.mint != leg.mint),
+                    EscrowError::InvalidBasket
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
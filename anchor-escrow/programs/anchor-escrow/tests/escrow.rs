@@ -0,0 +1,1218 @@
+// needs the sbf build of the program and of the transfer hook it's tested
+// with. `anchor run test-sbf` builds both, `cargo test-sbf` turns this
+// feature on
+#![cfg(feature = "test-sbf")]
+
+use anchor_escrow::{
+    errors::EscrowError,
+    state::{BasketLeg, Config, Escrow, LegacyEscrow, MAX_BASKET_LEGS},
+};
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        system_instruction,
+    },
+    system_program, AccountDeserialize, AccountSerialize, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{
+        self, get_associated_token_address_with_program_id,
+        spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+    },
+    token::spl_token,
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            transfer_fee::{instruction::initialize_transfer_fee_config, TransferFeeConfig},
+            transfer_hook, BaseStateWithExtensionsMut, ExtensionType, StateWithExtensions,
+            StateWithExtensionsMut,
+        },
+        instruction::{initialize_mint2, mint_to, transfer_checked},
+        state::{Account as TokenAccount, Mint},
+    },
+};
+use litesvm::LiteSVM;
+use solana_sdk::{
+    account::Account,
+    bpf_loader_upgradeable::{self, get_program_data_address, UpgradeableLoaderState},
+    clock::Clock,
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, state::ExtraAccountMetaList};
+use spl_transfer_hook_interface::{
+    get_extra_account_metas_address, instruction::ExecuteInstruction,
+};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+use transfer_hook_counter::Counter;
+
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+const DECIMALS: u8 = 6;
+const FUNDED: u64 = 1_000_000;
+
+// cargo test-sbf points SBF_OUT_DIR at its output, anchor build uses target/deploy
+fn program_so(name: &str) -> PathBuf {
+    env::var_os("SBF_OUT_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy"))
+        .join(format!("{name}.so"))
+}
+
+fn setup(protocol_fee_bps: u16) -> (LiteSVM, Keypair, Keypair, Pubkey) {
+    let mut svm = LiteSVM::new();
+    svm.add_program_from_file(anchor_escrow::ID, program_so("anchor_escrow"))
+        .expect("program not built, run `anchor build`");
+    svm.add_program_from_file(
+        transfer_hook_counter::ID,
+        program_so("transfer_hook_counter"),
+    )
+    .expect("transfer hook not built, run `anchor build`");
+
+    let maker = Keypair::new();
+    let taker = Keypair::new();
+    svm.airdrop(&maker.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    svm.airdrop(&taker.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+    // init_config is covered against an upgradeable deploy, write the config
+    // directly
+    let treasury = Pubkey::new_unique();
+    let (config, bump) = Pubkey::find_program_address(&[b"config"], &anchor_escrow::ID);
+    let mut data = Vec::new();
+    Config {
+        admin: Pubkey::new_unique(),
+        treasury,
+        fee_bps: protocol_fee_bps,
+        bump,
+    }
+    .try_serialize(&mut data)
+    .unwrap();
+    set_program_account(&mut svm, config, data);
+
+    (svm, maker, taker, treasury)
+}
+
+fn set_program_account(svm: &mut LiteSVM, address: Pubkey, data: Vec<u8>) {
+    set_account_owned_by(svm, address, anchor_escrow::ID, data);
+}
+
+fn set_account_owned_by(svm: &mut LiteSVM, address: Pubkey, owner: Pubkey, data: Vec<u8>) {
+    let account = Account {
+        lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+        data,
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    };
+
+    svm.set_account(address, account).unwrap();
+}
+
+/// Escrow deployed through the upgradeable loader with `authority` as upgrade
+/// authority, the program data account is what init_config checks.
+fn setup_upgradeable(authority: &Pubkey) -> LiteSVM {
+    let mut svm = LiteSVM::new();
+    let elf = fs::read(program_so("anchor_escrow")).expect("program not built, run `anchor build`");
+
+    let program_data = get_program_data_address(&anchor_escrow::ID);
+    let metadata_len = UpgradeableLoaderState::size_of_programdata_metadata();
+    let mut account = Account::new_data_with_space(
+        0,
+        &UpgradeableLoaderState::ProgramData {
+            slot: 0,
+            upgrade_authority_address: Some(*authority),
+        },
+        metadata_len + elf.len(),
+        &bpf_loader_upgradeable::ID,
+    )
+    .unwrap();
+    account.data[metadata_len..].copy_from_slice(&elf);
+    account.lamports = svm.minimum_balance_for_rent_exemption(account.data.len());
+    svm.set_account(program_data, account).unwrap();
+
+    let mut account = Account::new_data(
+        0,
+        &UpgradeableLoaderState::Program {
+            programdata_address: program_data,
+        },
+        &bpf_loader_upgradeable::ID,
+    )
+    .unwrap();
+    account.lamports = svm.minimum_balance_for_rent_exemption(account.data.len());
+    account.executable = true;
+    svm.set_account(anchor_escrow::ID, account).unwrap();
+
+    svm
+}
+
+fn init_config_ix(admin: &Pubkey, treasury: &Pubkey, fee_bps: u16) -> Instruction {
+    let accounts = anchor_escrow::accounts::InitConfig {
+        admin: *admin,
+        config: pda(&[b"config"]),
+        program: anchor_escrow::ID,
+        program_data: get_program_data_address(&anchor_escrow::ID),
+        system_program: system_program::ID,
+    };
+
+    Instruction {
+        program_id: anchor_escrow::ID,
+        accounts: accounts.to_account_metas(None),
+        data: anchor_escrow::instruction::InitConfig {
+            treasury: *treasury,
+            fee_bps,
+        }
+        .data(),
+    }
+}
+
+fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &anchor_escrow::ID).0
+}
+
+fn ata(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, token_program)
+}
+
+fn send(
+    svm: &mut LiteSVM,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), TransactionError> {
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&signers[0].pubkey()),
+        signers,
+        svm.latest_blockhash(),
+    );
+
+    let result = svm
+        .send_transaction(tx)
+        .map(|_| ())
+        .map_err(|failed| failed.err);
+    svm.expire_blockhash();
+
+    result
+}
+
+fn assert_custom_error(result: Result<(), TransactionError>, code: u32) {
+    assert_eq!(
+        result,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(code)
+        ))
+    );
+}
+
+/// Mint with `authority` as mint authority, Token-2022 mints can carry a
+/// transfer fee.
+fn create_mint(
+    svm: &mut LiteSVM,
+    authority: &Keypair,
+    token_program: &Pubkey,
+    transfer_fee_bps: Option<u16>,
+) -> Pubkey {
+    let mint = Keypair::new();
+    let extensions: &[ExtensionType] = match transfer_fee_bps {
+        Some(_) => &[ExtensionType::TransferFeeConfig],
+        None => &[],
+    };
+    let space = ExtensionType::try_calculate_account_len::<Mint>(extensions).unwrap();
+
+    let mut instructions = vec![system_instruction::create_account(
+        &authority.pubkey(),
+        &mint.pubkey(),
+        svm.minimum_balance_for_rent_exemption(space),
+        space as u64,
+        token_program,
+    )];
+    if let Some(fee_bps) = transfer_fee_bps {
+        instructions.push(
+            initialize_transfer_fee_config(
+                token_program,
+                &mint.pubkey(),
+                None,
+                None,
+                fee_bps,
+                u64::MAX,
+            )
+            .unwrap(),
+        );
+    }
+    instructions.push(
+        initialize_mint2(
+            token_program,
+            &mint.pubkey(),
+            &authority.pubkey(),
+            None,
+            DECIMALS,
+        )
+        .unwrap(),
+    );
+
+    send(svm, &instructions, &[authority, &mint]).unwrap();
+
+    mint.pubkey()
+}
+
+fn mint_tokens(
+    svm: &mut LiteSVM,
+    authority: &Keypair,
+    mint: &Pubkey,
+    owner: &Pubkey,
+    token_program: &Pubkey,
+    amount: u64,
+) -> Pubkey {
+    let token_account = ata(owner, mint, token_program);
+
+    send(
+        svm,
+        &[
+            create_associated_token_account_idempotent(
+                &authority.pubkey(),
+                owner,
+                mint,
+                token_program,
+            ),
+            mint_to(
+                token_program,
+                mint,
+                &token_account,
+                &authority.pubkey(),
+                &[],
+                amount,
+            )
+            .unwrap(),
+        ],
+        &[authority],
+    )
+    .unwrap();
+
+    token_account
+}
+
+fn balance(svm: &LiteSVM, token_account: &Pubkey) -> u64 {
+    match svm.get_account(token_account) {
+        Some(account) if account.lamports > 0 => {
+            StateWithExtensions::<TokenAccount>::unpack(&account.data)
+                .unwrap()
+                .base
+                .amount
+        }
+        _ => 0,
+    }
+}
+
+fn is_closed(svm: &LiteSVM, address: &Pubkey) -> bool {
+    match svm.get_account(address) {
+        Some(account) => account.lamports == 0,
+        None => true,
+    }
+}
+
+fn warp_to(svm: &mut LiteSVM, unix_timestamp: i64) {
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = unix_timestamp;
+    svm.set_sysvar(&clock);
+}
+
+/// Token-2022 mint whose transfers run the counter hook. The hook's
+/// validation account is written in place rather than through an initialize
+/// instruction the test hook doesn't have.
+fn create_hooked_mint(svm: &mut LiteSVM, authority: &Keypair) -> Pubkey {
+    let token_program = spl_token_2022::ID;
+    let mint = Keypair::new();
+    let space =
+        ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferHook]).unwrap();
+
+    let instructions = [
+        system_instruction::create_account(
+            &authority.pubkey(),
+            &mint.pubkey(),
+            svm.minimum_balance_for_rent_exemption(space),
+            space as u64,
+            &token_program,
+        ),
+        transfer_hook::instruction::initialize(
+            &token_program,
+            &mint.pubkey(),
+            None,
+            Some(transfer_hook_counter::ID),
+        )
+        .unwrap(),
+        initialize_mint2(
+            &token_program,
+            &mint.pubkey(),
+            &authority.pubkey(),
+            None,
+            DECIMALS,
+        )
+        .unwrap(),
+    ];
+    send(svm, &instructions, &[authority, &mint]).unwrap();
+
+    let mut data = vec![0; ExtraAccountMetaList::size_of(1).unwrap()];
+    ExtraAccountMetaList::init::<ExecuteInstruction>(
+        &mut data,
+        &[ExtraAccountMeta::new_with_pubkey(&hook_counter(), false, true).unwrap()],
+    )
+    .unwrap();
+    set_account_owned_by(
+        svm,
+        get_extra_account_metas_address(&mint.pubkey(), &transfer_hook_counter::ID),
+        transfer_hook_counter::ID,
+        data,
+    );
+
+    let mut data = Vec::new();
+    Counter { transfers: 0 }.try_serialize(&mut data).unwrap();
+    set_account_owned_by(svm, hook_counter(), transfer_hook_counter::ID, data);
+
+    mint.pubkey()
+}
+
+fn hook_counter() -> Pubkey {
+    Pubkey::find_program_address(&[b"counter"], &transfer_hook_counter::ID).0
+}
+
+fn hook_transfers(svm: &LiteSVM) -> u64 {
+    let account = svm.get_account(&hook_counter()).unwrap();
+
+    Counter::try_deserialize(&mut account.data.as_slice())
+        .unwrap()
+        .transfers
+}
+
+/// What the client's `transfer_hook_accounts` resolves to for a hooked mint.
+fn hook_accounts(mint: &Pubkey) -> [AccountMeta; 3] {
+    [
+        AccountMeta::new(hook_counter(), false),
+        AccountMeta::new_readonly(transfer_hook_counter::ID, false),
+        AccountMeta::new_readonly(
+            get_extra_account_metas_address(mint, &transfer_hook_counter::ID),
+            false,
+        ),
+    ]
+}
+
+/// Moves the mint's transfer fee to `fee_bps` for the current epoch, the way
+/// a fee authority's change looks once it has taken effect.
+fn set_transfer_fee(svm: &mut LiteSVM, mint: &Pubkey, fee_bps: u16) {
+    let mut account = svm.get_account(mint).unwrap();
+    let mut state = StateWithExtensionsMut::<Mint>::unpack(&mut account.data).unwrap();
+    let config = state.get_extension_mut::<TransferFeeConfig>().unwrap();
+    config.older_transfer_fee.transfer_fee_basis_points = fee_bps.into();
+    config.newer_transfer_fee.transfer_fee_basis_points = fee_bps.into();
+
+    svm.set_account(*mint, account).unwrap();
+}
+
+/// `(mint, token program, amount)` of one basket leg.
+type Leg = (Pubkey, Pubkey, u64);
+
+fn basket_pda(maker: &Pubkey, seed: u64) -> Pubkey {
+    pda(&[b"basket", maker.as_ref(), &seed.to_le_bytes()])
+}
+
+fn make_basket_ix(maker: &Pubkey, seed: u64, offered: &[Leg], receive: &[Leg]) -> Instruction {
+    let basket = basket_pda(maker, seed);
+    let legs = |legs: &[Leg]| {
+        legs.iter()
+            .map(|(mint, _, amount)| BasketLeg {
+                mint: *mint,
+                amount: *amount,
+            })
+            .collect()
+    };
+
+    let mut accounts = anchor_escrow::accounts::MakeBasket {
+        maker: *maker,
+        basket,
+        config: pda(&[b"config"]),
+        associated_token_program: associated_token::ID,
+        system_program: system_program::ID,
+    }
+    .to_account_metas(None);
+    for (mint, token_program, _) in offered {
+        accounts.extend([
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new(ata(maker, mint, token_program), false),
+            AccountMeta::new(ata(&basket, mint, token_program), false),
+        ]);
+    }
+    accounts.extend(
+        receive
+            .iter()
+            .map(|(mint, _, _)| AccountMeta::new_readonly(*mint, false)),
+    );
+
+    Instruction {
+        program_id: anchor_escrow::ID,
+        accounts,
+        data: anchor_escrow::instruction::MakeBasket {
+            seed,
+            offered: legs(offered),
+            receive: legs(receive),
+        }
+        .data(),
+    }
+}
+
+fn take_basket_ix(
+    taker: &Pubkey,
+    maker: &Pubkey,
+    seed: u64,
+    treasury: &Pubkey,
+    offered: &[Leg],
+    receive: &[Leg],
+) -> Instruction {
+    let basket = basket_pda(maker, seed);
+
+    let mut accounts = anchor_escrow::accounts::TakeBasket {
+        taker: *taker,
+        maker: *maker,
+        basket,
+        config: pda(&[b"config"]),
+        treasury: *treasury,
+        associated_token_program: associated_token::ID,
+        system_program: system_program::ID,
+    }
+    .to_account_metas(None);
+    for (mint, token_program, _) in offered {
+        accounts.extend([
+            AccountMeta::new(*mint, false),
+            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new(ata(&basket, mint, token_program), false),
+            AccountMeta::new(ata(taker, mint, token_program), false),
+            AccountMeta::new(ata(treasury, mint, token_program), false),
+        ]);
+    }
+    for (mint, token_program, _) in receive {
+        accounts.extend([
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new(ata(taker, mint, token_program), false),
+            AccountMeta::new(ata(maker, mint, token_program), false),
+        ]);
+    }
+
+    Instruction {
+        program_id: anchor_escrow::ID,
+        accounts,
+        data: anchor_escrow::instruction::TakeBasket {}.data(),
+    }
+}
+
+/// A maker holding `FUNDED` of mint a and a taker holding `FUNDED` of mint b,
+/// both mints under the same token program.
+struct Offer {
+    maker: Pubkey,
+    seed: u64,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+    token_program: Pubkey,
+}
+
+impl Offer {
+    fn new(
+        svm: &mut LiteSVM,
+        maker: &Keypair,
+        taker: &Keypair,
+        token_program: Pubkey,
+        transfer_fee_bps: Option<u16>,
+    ) -> Self {
+        let mint_a = create_mint(svm, maker, &token_program, transfer_fee_bps);
+        let mint_b = create_mint(svm, maker, &token_program, transfer_fee_bps);
+
+        mint_tokens(svm, maker, &mint_a, &maker.pubkey(), &token_program, FUNDED);
+        mint_tokens(svm, maker, &mint_b, &taker.pubkey(), &token_program, FUNDED);
+
+        Offer {
+            maker: maker.pubkey(),
+            seed: 7,
+            mint_a,
+            mint_b,
+            token_program,
+        }
+    }
+
+    fn escrow(&self) -> Pubkey {
+        pda(&[b"escrow", self.maker.as_ref(), &self.seed.to_le_bytes()])
+    }
+
+    fn vault(&self) -> Pubkey {
+        ata(&self.escrow(), &self.mint_a, &self.token_program)
+    }
+
+    fn ata_a(&self, owner: &Pubkey) -> Pubkey {
+        ata(owner, &self.mint_a, &self.token_program)
+    }
+
+    fn ata_b(&self, owner: &Pubkey) -> Pubkey {
+        ata(owner, &self.mint_b, &self.token_program)
+    }
+
+    fn state(&self, svm: &LiteSVM) -> Escrow {
+        let account = svm.get_account(&self.escrow()).unwrap();
+
+        Escrow::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    fn make_ix(
+        &self,
+        recieve: u64,
+        amount: u64,
+        taker: Option<Pubkey>,
+        expires_at: Option<i64>,
+        crank_tip: u64,
+    ) -> Instruction {
+        let accounts = anchor_escrow::accounts::Make {
+            maker: self.maker,
+            escrow: self.escrow(),
+            config: pda(&[b"config"]),
+            mint_a: self.mint_a,
+            mint_b: self.mint_b,
+            maker_ata_token_a: self.ata_a(&self.maker),
+            vault: self.vault(),
+            associated_token_program: associated_token::ID,
+            token_program: self.token_program,
+            system_program: system_program::ID,
+        };
+
+        Instruction {
+            program_id: anchor_escrow::ID,
+            accounts: accounts.to_account_metas(None),
+            data: anchor_escrow::instruction::Make {
+                seed: self.seed,
+                recieve,
+                amount,
+                taker,
+                expires_at,
+                crank_tip,
+            }
+            .data(),
+        }
+    }
+
+    fn take_ix(
+        &self,
+        taker: &Pubkey,
+        treasury: &Pubkey,
+        expected_receive: u64,
+        expected_amount_a: u64,
+    ) -> Instruction {
+        let accounts = anchor_escrow::accounts::Take {
+            taker: *taker,
+            maker: self.maker,
+            escrow: self.escrow(),
+            config: pda(&[b"config"]),
+            mint_a: self.mint_a,
+            mint_b: self.mint_b,
+            vault: self.vault(),
+            taker_ata_token_a: self.ata_a(taker),
+            taker_ata_token_b: self.ata_b(taker),
+            maker_ata_token_b: self.ata_b(&self.maker),
+            treasury: *treasury,
+            treasury_ata_token_a: Some(self.ata_a(treasury)),
+            associated_token_program: associated_token::ID,
+            token_program: self.token_program,
+            system_program: system_program::ID,
+        };
+
+        Instruction {
+            program_id: anchor_escrow::ID,
+            accounts: accounts.to_account_metas(None),
+            data: anchor_escrow::instruction::Take {
+                expected_receive,
+                expected_amount_a,
+            }
+            .data(),
+        }
+    }
+
+    fn take_partial_ix(
+        &self,
+        taker: &Pubkey,
+        treasury: &Pubkey,
+        amount_b: u64,
+        min_amount_a: u64,
+    ) -> Instruction {
+        let accounts = anchor_escrow::accounts::TakePartial {
+            taker: *taker,
+            maker: self.maker,
+            escrow: self.escrow(),
+            config: pda(&[b"config"]),
+            mint_a: self.mint_a,
+            mint_b: self.mint_b,
+            vault: self.vault(),
+            taker_ata_token_a: self.ata_a(taker),
+            taker_ata_token_b: self.ata_b(taker),
+            maker_ata_token_b: self.ata_b(&self.maker),
+            treasury: *treasury,
+            treasury_ata_token_a: Some(self.ata_a(treasury)),
+            associated_token_program: associated_token::ID,
+            token_program: self.token_program,
+            system_program: system_program::ID,
+        };
+
+        Instruction {
+            program_id: anchor_escrow::ID,
+            accounts: accounts.to_account_metas(None),
+            data: anchor_escrow::instruction::TakePartial {
+                amount_b,
+                min_amount_a,
+            }
+            .data(),
+        }
+    }
+
+    fn crank_expired_ix(&self, cranker: &Pubkey) -> Instruction {
+        let accounts = anchor_escrow::accounts::CrankExpired {
+            cranker: *cranker,
+            maker: self.maker,
+            escrow: self.escrow(),
+            mint_a: self.mint_a,
+            vault: self.vault(),
+            maker_ata_token_a: self.ata_a(&self.maker),
+            associated_token_program: associated_token::ID,
+            token_program: self.token_program,
+            system_program: system_program::ID,
+        };
+
+        Instruction {
+            program_id: anchor_escrow::ID,
+            accounts: accounts.to_account_metas(None),
+            data: anchor_escrow::instruction::CrankExpired {}.data(),
+        }
+    }
+
+    fn amend_ix(&self, recieve: u64, deposit: u64, withdraw: u64) -> Instruction {
+        let accounts = anchor_escrow::accounts::Amend {
+            maker: self.maker,
+            escrow: self.escrow(),
+            mint_a: self.mint_a,
+            mint_b: self.mint_b,
+            vault: self.vault(),
+            maker_ata_token_a: self.ata_a(&self.maker),
+            associated_token_program: associated_token::ID,
+            token_program: self.token_program,
+            system_program: system_program::ID,
+        };
+
+        Instruction {
+            program_id: anchor_escrow::ID,
+            accounts: accounts.to_account_metas(None),
+            data: anchor_escrow::instruction::Amend {
+                recieve,
+                deposit,
+                withdraw,
+            }
+            .data(),
+        }
+    }
+
+    fn migrate_ix(&self, payer: &Pubkey) -> Instruction {
+        let accounts = anchor_escrow::accounts::MigrateEscrow {
+            payer: *payer,
+            escrow: self.escrow(),
+            mint_a: self.mint_a,
+            mint_b: self.mint_b,
+            vault: self.vault(),
+            associated_token_program: associated_token::ID,
+            token_program: self.token_program,
+            system_program: system_program::ID,
+        };
+
+        Instruction {
+            program_id: anchor_escrow::ID,
+            accounts: accounts.to_account_metas(None),
+            data: anchor_escrow::instruction::MigrateEscrow {}.data(),
+        }
+    }
+}
+
+#[test]
+fn take_partial_fills_pro_rata_and_closes_on_the_last_fill() {
+    let (mut svm, maker, taker, treasury) = setup(0);
+    let offer = Offer::new(&mut svm, &maker, &taker, spl_token::ID, None);
+
+    send(&mut svm, &[offer.make_ix(3, 100, None, None, 0)], &[&maker]).unwrap();
+
+    // 1 * 100 / 3 rounds down to 33, the maker keeps the remainder
+    let fill = offer.take_partial_ix(&taker.pubkey(), &treasury, 1, 33);
+    send(&mut svm, &[fill], &[&taker]).unwrap();
+
+    let escrow = offer.state(&svm);
+    assert_eq!(escrow.recieve, 2);
+    assert_eq!(escrow.deposited, 67);
+    assert_eq!(balance(&svm, &offer.vault()), 67);
+    assert_eq!(balance(&svm, &offer.ata_a(&taker.pubkey())), 33);
+    assert_eq!(balance(&svm, &offer.ata_b(&maker.pubkey())), 1);
+
+    // priced off what's left, 1 * 67 / 2 rounds down to 33
+    let fill = offer.take_partial_ix(&taker.pubkey(), &treasury, 1, 33);
+    send(&mut svm, &[fill], &[&taker]).unwrap();
+
+    assert_eq!(offer.state(&svm).deposited, 34);
+    assert_eq!(balance(&svm, &offer.ata_a(&taker.pubkey())), 66);
+
+    // the last fill sweeps whatever rounding left behind
+    let fill = offer.take_partial_ix(&taker.pubkey(), &treasury, 1, 34);
+    send(&mut svm, &[fill], &[&taker]).unwrap();
+
+    assert_eq!(balance(&svm, &offer.ata_a(&taker.pubkey())), 100);
+    assert_eq!(balance(&svm, &offer.ata_b(&maker.pubkey())), 3);
+    assert!(is_closed(&svm, &offer.vault()));
+    assert!(is_closed(&svm, &offer.escrow()));
+}
+
+#[test]
+fn take_partial_rounding_to_nothing_fails() {
+    let (mut svm, maker, taker, treasury) = setup(0);
+    let offer = Offer::new(&mut svm, &maker, &taker, spl_token::ID, None);
+
+    send(
+        &mut svm,
+        &[offer.make_ix(1_000, 10, None, None, 0)],
+        &[&maker],
+    )
+    .unwrap();
+
+    let fill = offer.take_partial_ix(&taker.pubkey(), &treasury, 1, 0);
+    let result = send(&mut svm, &[fill], &[&taker]);
+
+    assert_custom_error(result, EscrowError::InvalidAmount.into());
+}
+
+#[test]
+fn take_partial_below_min_amount_fails() {
+    let (mut svm, maker, taker, treasury) = setup(0);
+    let offer = Offer::new(&mut svm, &maker, &taker, spl_token::ID, None);
+
+    send(&mut svm, &[offer.make_ix(3, 100, None, None, 0)], &[&maker]).unwrap();
+
+    let fill = offer.take_partial_ix(&taker.pubkey(), &treasury, 1, 34);
+    let result = send(&mut svm, &[fill], &[&taker]);
+
+    assert_custom_error(result, EscrowError::OfferChanged.into());
+}
+
+#[test]
+fn crank_expired_refunds_maker_and_pays_tip() {
+    let (mut svm, maker, taker, treasury) = setup(0);
+    let offer = Offer::new(&mut svm, &maker, &taker, spl_token::ID, None);
+    let now = svm.get_sysvar::<Clock>().unix_timestamp;
+    let tip = LAMPORTS_PER_SOL / 100;
+
+    let make = offer.make_ix(10, 100, None, Some(now + 60), tip);
+    send(&mut svm, &[make], &[&maker]).unwrap();
+
+    let cranker = Keypair::new();
+    svm.airdrop(&cranker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+    let result = send(
+        &mut svm,
+        &[offer.crank_expired_ix(&cranker.pubkey())],
+        &[&cranker],
+    );
+    assert_custom_error(result, EscrowError::OfferNotExpired.into());
+
+    warp_to(&mut svm, now + 60);
+
+    let take = offer.take_ix(&taker.pubkey(), &treasury, 10, 100);
+    let result = send(&mut svm, &[take], &[&taker]);
+    assert_custom_error(result, EscrowError::OfferExpired.into());
+
+    let before = svm.get_balance(&cranker.pubkey()).unwrap();
+    send(
+        &mut svm,
+        &[offer.crank_expired_ix(&cranker.pubkey())],
+        &[&cranker],
+    )
+    .unwrap();
+
+    assert!(svm.get_balance(&cranker.pubkey()).unwrap() > before);
+    assert_eq!(balance(&svm, &offer.ata_a(&maker.pubkey())), FUNDED);
+    assert!(is_closed(&svm, &offer.escrow()));
+}
+
+#[test]
+fn migrate_escrow_makes_legacy_offers_takeable() {
+    let (mut svm, maker, taker, treasury) = setup(0);
+    let offer = Offer::new(&mut svm, &maker, &taker, spl_token::ID, None);
+    let (_, bump) = Pubkey::find_program_address(
+        &[
+            b"escrow",
+            maker.pubkey().as_ref(),
+            &offer.seed.to_le_bytes(),
+        ],
+        &anchor_escrow::ID,
+    );
+
+    // an escrow made before the layout grew, with its vault already funded
+    let mut data = Vec::new();
+    LegacyEscrow {
+        seed: offer.seed,
+        maker: maker.pubkey(),
+        mint_a: offer.mint_a,
+        mint_b: offer.mint_b,
+        recieve: 10,
+        bump,
+    }
+    .try_serialize(&mut data)
+    .unwrap();
+    set_program_account(&mut svm, offer.escrow(), data);
+    mint_tokens(
+        &mut svm,
+        &maker,
+        &offer.mint_a,
+        &offer.escrow(),
+        &spl_token::ID,
+        100,
+    );
+
+    let take = || offer.take_ix(&taker.pubkey(), &treasury, 10, 100);
+
+    let result = send(&mut svm, &[take()], &[&taker]);
+    assert_custom_error(
+        result,
+        anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into(),
+    );
+
+    send(&mut svm, &[offer.migrate_ix(&taker.pubkey())], &[&taker]).unwrap();
+
+    let escrow = offer.state(&svm);
+    assert_eq!(escrow.deposited, 100);
+    assert_eq!(escrow.taker, None);
+    assert_eq!(escrow.protocol_fee_bps, 0);
+
+    send(&mut svm, &[take()], &[&taker]).unwrap();
+
+    assert_eq!(balance(&svm, &offer.ata_a(&taker.pubkey())), 100);
+    assert_eq!(balance(&svm, &offer.ata_b(&maker.pubkey())), 10);
+}
+
+#[test]
+fn take_by_other_than_designated_taker_fails() {
+    let (mut svm, maker, taker, treasury) = setup(0);
+    let offer = Offer::new(&mut svm, &maker, &taker, spl_token::ID, None);
+
+    let make = offer.make_ix(10, 100, Some(Pubkey::new_unique()), None, 0);
+    send(&mut svm, &[make], &[&maker]).unwrap();
+
+    let take = offer.take_ix(&taker.pubkey(), &treasury, 10, 100);
+    let result = send(&mut svm, &[take], &[&taker]);
+
+    assert_custom_error(result, EscrowError::InvalidTaker.into());
+}
+
+#[test]
+fn take_nets_maker_the_ask_and_closes_vault_with_withheld_fees() {
+    let (mut svm, maker, taker, treasury) = setup(0);
+    // 1% transfer fee on both mints
+    let offer = Offer::new(&mut svm, &maker, &taker, spl_token_2022::ID, Some(100));
+
+    send(
+        &mut svm,
+        &[offer.make_ix(1_000, 10_000, None, None, 0)],
+        &[&maker],
+    )
+    .unwrap();
+
+    // the vault only got the deposit net of the fee, which it now withholds
+    assert_eq!(offer.state(&svm).deposited, 9_900);
+
+    let take = offer.take_ix(&taker.pubkey(), &treasury, 1_000, 9_900);
+    send(&mut svm, &[take], &[&taker]).unwrap();
+
+    assert_eq!(balance(&svm, &offer.ata_b(&maker.pubkey())), 1_000);
+    assert_eq!(balance(&svm, &offer.ata_a(&taker.pubkey())), 9_801);
+    assert!(is_closed(&svm, &offer.vault()));
+    assert!(is_closed(&svm, &offer.escrow()));
+    // no protocol fee, so the taker didn't pay for a treasury token account
+    assert!(is_closed(&svm, &offer.ata_a(&treasury)));
+}
+
+#[test]
+fn take_pays_protocol_fee_to_treasury() {
+    let (mut svm, maker, taker, treasury) = setup(100);
+    let offer = Offer::new(&mut svm, &maker, &taker, spl_token::ID, None);
+
+    send(
+        &mut svm,
+        &[offer.make_ix(10, 10_000, None, None, 0)],
+        &[&maker],
+    )
+    .unwrap();
+
+    // a fee is owed, so the treasury token account can't be left out
+    let mut take = offer.take_ix(&taker.pubkey(), &treasury, 10, 10_000);
+    let treasury_ata = offer.ata_a(&treasury);
+    let meta = take
+        .accounts
+        .iter_mut()
+        .find(|meta| meta.pubkey == treasury_ata)
+        .unwrap();
+    *meta = AccountMeta::new_readonly(anchor_escrow::ID, false);
+    let result = send(&mut svm, &[take], &[&taker]);
+    assert_custom_error(result, EscrowError::InvalidTreasury.into());
+
+    let take = offer.take_ix(&taker.pubkey(), &treasury, 10, 10_000);
+    send(&mut svm, &[take], &[&taker]).unwrap();
+
+    assert_eq!(balance(&svm, &treasury_ata), 100);
+    assert_eq!(balance(&svm, &offer.ata_a(&taker.pubkey())), 9_900);
+    assert_eq!(balance(&svm, &offer.ata_b(&maker.pubkey())), 10);
+}
+
+#[test]
+fn init_config_needs_the_upgrade_authority() {
+    let authority = Keypair::new();
+    let impostor = Keypair::new();
+    let mut svm = setup_upgradeable(&authority.pubkey());
+    svm.airdrop(&authority.pubkey(), LAMPORTS_PER_SOL).unwrap();
+    svm.airdrop(&impostor.pubkey(), LAMPORTS_PER_SOL).unwrap();
+    let treasury = Pubkey::new_unique();
+
+    let init = init_config_ix(&impostor.pubkey(), &treasury, 100);
+    let result = send(&mut svm, &[init], &[&impostor]);
+    assert_custom_error(result, EscrowError::InvalidAdmin.into());
+
+    let init = init_config_ix(&authority.pubkey(), &treasury, 100);
+    send(&mut svm, &[init], &[&authority]).unwrap();
+
+    let account = svm.get_account(&pda(&[b"config"])).unwrap();
+    let config = Config::try_deserialize(&mut account.data.as_slice()).unwrap();
+    assert_eq!(config.admin, authority.pubkey());
+    assert_eq!(config.treasury, treasury);
+    assert_eq!(config.fee_bps, 100);
+}
+
+#[test]
+fn take_after_a_transfer_fee_change_fails() {
+    let (mut svm, maker, taker, treasury) = setup(0);
+    let offer = Offer::new(&mut svm, &maker, &taker, spl_token_2022::ID, Some(100));
+
+    send(
+        &mut svm,
+        &[offer.make_ix(1_000, 10_000, None, None, 0)],
+        &[&maker],
+    )
+    .unwrap();
+
+    // a higher fee on the taker's side would short the maker of the ask
+    set_transfer_fee(&mut svm, &offer.mint_b, 500);
+
+    let take = offer.take_ix(&taker.pubkey(), &treasury, 1_000, 9_900);
+    let result = send(&mut svm, &[take], &[&taker]);
+
+    assert_custom_error(result, EscrowError::TransferFeeChanged.into());
+}
+
+#[test]
+fn hooked_mint_runs_its_hook_on_make_and_take() {
+    let (mut svm, maker, taker, treasury) = setup(0);
+    let token_program = spl_token_2022::ID;
+    let mint_a = create_hooked_mint(&mut svm, &maker);
+    let mint_b = create_mint(&mut svm, &maker, &token_program, None);
+    mint_tokens(
+        &mut svm,
+        &maker,
+        &mint_a,
+        &maker.pubkey(),
+        &token_program,
+        FUNDED,
+    );
+    mint_tokens(
+        &mut svm,
+        &maker,
+        &mint_b,
+        &taker.pubkey(),
+        &token_program,
+        FUNDED,
+    );
+    let offer = Offer {
+        maker: maker.pubkey(),
+        seed: 7,
+        mint_a,
+        mint_b,
+        token_program,
+    };
+
+    let mut make = offer.make_ix(10, 100, None, None, 0);
+    make.accounts.extend(hook_accounts(&mint_a));
+    send(&mut svm, &[make], &[&maker]).unwrap();
+    assert_eq!(hook_transfers(&svm), 1);
+
+    // Token-2022 can't run the hook without its accounts and fails the transfer
+    let mut take = offer.take_ix(&taker.pubkey(), &treasury, 10, 100);
+    assert!(send(&mut svm, &[take.clone()], &[&taker]).is_err());
+
+    take.accounts.extend(hook_accounts(&mint_a));
+    send(&mut svm, &[take], &[&taker]).unwrap();
+
+    assert_eq!(hook_transfers(&svm), 2);
+    assert_eq!(balance(&svm, &offer.ata_a(&taker.pubkey())), 100);
+    assert_eq!(balance(&svm, &offer.ata_b(&maker.pubkey())), 10);
+    assert!(is_closed(&svm, &offer.escrow()));
+}
+
+#[test]
+fn amend_changes_terms_and_dust_does_not_block_take() {
+    let (mut svm, maker, taker, treasury) = setup(0);
+    let offer = Offer::new(&mut svm, &maker, &taker, spl_token::ID, None);
+
+    send(
+        &mut svm,
+        &[offer.make_ix(10, 100, None, None, 0)],
+        &[&maker],
+    )
+    .unwrap();
+    send(&mut svm, &[offer.amend_ix(20, 0, 0)], &[&maker]).unwrap();
+
+    let result = send(
+        &mut svm,
+        &[offer.take_ix(&taker.pubkey(), &treasury, 10, 100)],
+        &[&taker],
+    );
+    assert_custom_error(result, EscrowError::OfferChanged.into());
+
+    // anyone can send tokens to the vault, the quoted terms still hold
+    let dust = transfer_checked(
+        &spl_token::ID,
+        &offer.ata_a(&maker.pubkey()),
+        &offer.mint_a,
+        &offer.vault(),
+        &maker.pubkey(),
+        &[],
+        1,
+        DECIMALS,
+    )
+    .unwrap();
+    send(&mut svm, &[dust], &[&maker]).unwrap();
+
+    send(
+        &mut svm,
+        &[offer.take_ix(&taker.pubkey(), &treasury, 20, 100)],
+        &[&taker],
+    )
+    .unwrap();
+
+    assert_eq!(balance(&svm, &offer.ata_a(&taker.pubkey())), 101);
+    assert_eq!(balance(&svm, &offer.ata_b(&maker.pubkey())), 20);
+}
+
+#[test]
+fn basket_settles_legs_across_token_programs() {
+    let (mut svm, maker, taker, treasury) = setup(0);
+    let token = spl_token::ID;
+    let token_2022 = spl_token_2022::ID;
+
+    let mint_x = create_mint(&mut svm, &maker, &token, None);
+    let mint_y = create_mint(&mut svm, &maker, &token_2022, None);
+    let mint_z = create_mint(&mut svm, &maker, &token, None);
+    mint_tokens(&mut svm, &maker, &mint_x, &maker.pubkey(), &token, FUNDED);
+    mint_tokens(
+        &mut svm,
+        &maker,
+        &mint_y,
+        &maker.pubkey(),
+        &token_2022,
+        FUNDED,
+    );
+    mint_tokens(&mut svm, &maker, &mint_z, &taker.pubkey(), &token, FUNDED);
+
+    let seed = 3;
+    let offered = [(mint_x, token, 100), (mint_y, token_2022, 200)];
+    let receive = [(mint_z, token, 50)];
+
+    let make = make_basket_ix(&maker.pubkey(), seed, &offered, &receive);
+    send(&mut svm, &[make], &[&maker]).unwrap();
+
+    let take = take_basket_ix(
+        &taker.pubkey(),
+        &maker.pubkey(),
+        seed,
+        &treasury,
+        &offered,
+        &receive,
+    );
+    send(&mut svm, &[take], &[&taker]).unwrap();
+
+    let basket = basket_pda(&maker.pubkey(), seed);
+    for (mint, token_program, amount) in offered {
+        assert_eq!(
+            balance(&svm, &ata(&taker.pubkey(), &mint, &token_program)),
+            amount
+        );
+        assert!(is_closed(&svm, &ata(&basket, &mint, &token_program)));
+        assert!(is_closed(&svm, &ata(&treasury, &mint, &token_program)));
+    }
+    assert_eq!(balance(&svm, &ata(&maker.pubkey(), &mint_z, &token)), 50);
+    assert!(is_closed(&svm, &basket));
+}
+
+#[test]
+fn basket_over_the_leg_cap_fails() {
+    let (mut svm, maker, _, _) = setup(0);
+    let legs: Vec<Leg> = (0..=MAX_BASKET_LEGS)
+        .map(|_| (Pubkey::new_unique(), spl_token::ID, 1))
+        .collect();
+
+    let make = make_basket_ix(&maker.pubkey(), 3, &legs, &legs[..1]);
+    let result = send(&mut svm, &[make], &[&maker]);
+
+    assert_custom_error(result, EscrowError::InvalidBasket.into());
+}
+
+#[test]
+fn basket_take_after_a_receive_fee_change_fails() {
+    let (mut svm, maker, taker, treasury) = setup(0);
+    let token_2022 = spl_token_2022::ID;
+
+    let mint_x = create_mint(&mut svm, &maker, &token_2022, None);
+    let mint_z = create_mint(&mut svm, &maker, &token_2022, Some(100));
+    mint_tokens(
+        &mut svm,
+        &maker,
+        &mint_x,
+        &maker.pubkey(),
+        &token_2022,
+        FUNDED,
+    );
+    mint_tokens(
+        &mut svm,
+        &maker,
+        &mint_z,
+        &taker.pubkey(),
+        &token_2022,
+        FUNDED,
+    );
+
+    let seed = 3;
+    let offered = [(mint_x, token_2022, 100)];
+    let receive = [(mint_z, token_2022, 1_000)];
+
+    let make = make_basket_ix(&maker.pubkey(), seed, &offered, &receive);
+    send(&mut svm, &[make], &[&maker]).unwrap();
+
+    // the taker would have to pay more than quoted to net the maker the leg
+    set_transfer_fee(&mut svm, &mint_z, 500);
+
+    let take = take_basket_ix(
+        &taker.pubkey(),
+        &maker.pubkey(),
+        seed,
+        &treasury,
+        &offered,
+        &receive,
+    );
+    let result = send(&mut svm, &[take], &[&taker]);
+
+    assert_custom_error(result, EscrowError::TransferFeeChanged.into());
+}
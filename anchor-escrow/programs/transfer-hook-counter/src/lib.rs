@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+declare_id!("7qrPdEXENcNZJ2DsaQp3Yjw9tQPL5Fxp7e2B47pxDDnc");
+
+/// Transfer hook for the escrow's LiteSVM tests. Its mints resolve to a single
+/// extra account, a counter bumped on every transfer, so a test can see
+/// whether the escrow forwarded the hook accounts along with each transfer.
+#[program]
+pub mod transfer_hook_counter {
+    use super::*;
+
+    // spl-transfer-hook-interface `Execute`
+    #[instruction(discriminator = [105, 37, 101, 197, 75, 251, 102, 26])]
+    pub fn execute(ctx: Context<Execute>, _amount: u64) -> Result<()> {
+        ctx.accounts.counter.transfers += 1;
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Execute<'info> {
+    /// CHECK: passed through by Token-2022
+    pub source: UncheckedAccount<'info>,
+
+    /// CHECK: passed through by Token-2022
+    pub mint: UncheckedAccount<'info>,
+
+    /// CHECK: passed through by Token-2022
+    pub destination: UncheckedAccount<'info>,
+
+    /// CHECK: passed through by Token-2022
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: Token-2022 resolved the extra accounts from it already
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    #[account(mut, seeds = [b"counter"], bump)]
+    pub counter: Account<'info, Counter>,
+}
+
+#[account]
+pub struct Counter {
+    pub transfers: u64,
+}
@@ -0,0 +1,178 @@
+use anchor_lang::{
+    error::ERROR_CODE_OFFSET,
+    prelude::Pubkey,
+    solana_program::instruction::{Instruction, InstructionError},
+    system_program, InstructionData, ToAccountMetas,
+};
+
+pub use anchor_vault::{errors::VaultError, ID};
+
+// codes are assigned by position, so the list keeps declaration order. the
+// match is exhaustive, a variant missing from the list doesn't compile
+macro_rules! vault_errors {
+    ($($variant:ident),* $(,)?) => {
+        const VAULT_ERRORS: &[VaultError] = &[$(VaultError::$variant),*];
+
+        const _: fn(VaultError) = |error| match error {
+            $(VaultError::$variant)|* => {}
+        };
+    };
+}
+
+vault_errors!(
+    VaultAlreadyExists,
+    InvalidAmount,
+    InsufficientFunds,
+    BelowRentMinimum,
+    VaultLocked,
+    InvalidUnlockTime,
+    GuardianApprovalRequired,
+    InvalidGuardianConfig,
+    NotAGuardian,
+    AlreadyApproved,
+    NotEnoughApprovals,
+    DelegationExpired,
+    AllowanceExceeded,
+    InvalidSpendPolicy,
+    RateLimitExceeded,
+    InvalidBeneficiary,
+    OwnerStillActive,
+    TooManyVaults,
+    VaultNotEmpty,
+    Unauthorized,
+    InvalidPendingOwner,
+    FundsStaked,
+    StakeAccountInUse,
+    WithdrawQueueRequired,
+    WithdrawNotReady,
+    InvalidQueuePolicy,
+    DestinationNotAllowed,
+    InvalidDestination,
+    VaultFrozen,
+    VaultNotFrozen,
+    TooManyDelegates,
+    TooManyTokenMints,
+    DelegationRevoked,
+    RequestMintMismatch,
+);
+
+/// Lamport vault of `creator`'s vault number `vault_id`.
+pub fn vault_pda(creator: &Pubkey, vault_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vault", creator.as_ref(), &vault_id.to_le_bytes()], &ID)
+}
+
+pub fn vault_state_pda(creator: &Pubkey, vault_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"state", creator.as_ref(), &vault_id.to_le_bytes()], &ID)
+}
+
+/// Registry listing every vault `owner` currently owns, wherever it was created.
+pub fn registry_pda(owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"registry", owner.as_ref()], &ID)
+}
+
+pub fn event_authority_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"__event_authority"], &ID)
+}
+
+/// Accounts shared by deposit, top up and withdraw. `creator` stays the seed key
+/// after an ownership transfer, `signer` is whoever currently owns the vault.
+pub fn vault_action_accounts(
+    signer: &Pubkey,
+    creator: &Pubkey,
+    vault_id: u64,
+) -> anchor_vault::accounts::VaultAction {
+    anchor_vault::accounts::VaultAction {
+        signer: *signer,
+        creator: *creator,
+        vault: vault_pda(creator, vault_id).0,
+        vault_state: vault_state_pda(creator, vault_id).0,
+        registry: registry_pda(signer).0,
+        system_program: system_program::ID,
+        event_authority: event_authority_pda().0,
+        program: ID,
+    }
+}
+
+/// Opens and funds a new vault created by `owner`.
+pub fn deposit_ix(owner: &Pubkey, vault_id: u64, amount: u64) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: vault_action_accounts(owner, owner, vault_id).to_account_metas(None),
+        data: anchor_vault::instruction::Deposit { vault_id, amount }.data(),
+    }
+}
+
+/// Withdraws the whole balance of a vault created and still owned by `owner`.
+pub fn withdraw_ix(owner: &Pubkey, vault_id: u64) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: vault_action_accounts(owner, owner, vault_id).to_account_metas(None),
+        data: anchor_vault::instruction::Withdraw { vault_id }.data(),
+    }
+}
+
+/// Maps a custom program error code back to the vault error that raised it.
+pub fn decode_error_code(code: u32) -> Option<VaultError> {
+    let index = code.checked_sub(ERROR_CODE_OFFSET)?;
+    VAULT_ERRORS.get(index as usize).copied()
+}
+
+/// Extracts the vault error from a failed instruction, `None` for anything
+/// that wasn't raised by the vault program itself.
+pub fn decode_error(error: &InstructionError) -> Option<VaultError> {
+    match error {
+        InstructionError::Custom(code) => decode_error_code(*code),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_error_decodes_back_to_itself() {
+        for (index, error) in VAULT_ERRORS.iter().enumerate() {
+            let code = u32::from(*error);
+            assert_eq!(
+                code,
+                ERROR_CODE_OFFSET + index as u32,
+                "{error} out of order"
+            );
+            assert_eq!(decode_error_code(code).map(u32::from), Some(code));
+        }
+
+        let past_the_end = ERROR_CODE_OFFSET + VAULT_ERRORS.len() as u32;
+        assert!(decode_error_code(past_the_end).is_none());
+    }
+
+    #[test]
+    fn decode_error_only_reads_custom_codes() {
+        let locked = InstructionError::Custom(VaultError::VaultLocked.into());
+        assert_eq!(
+            decode_error(&locked).map(u32::from),
+            Some(VaultError::VaultLocked.into())
+        );
+
+        // anchor's own constraint errors sit below the offset
+        assert!(decode_error(&InstructionError::Custom(2006)).is_none());
+        assert!(decode_error(&InstructionError::MissingRequiredSignature).is_none());
+    }
+
+    #[test]
+    fn vault_pda_depends_on_creator_and_id() {
+        let creator = Pubkey::new_unique();
+        let (vault, bump) = vault_pda(&creator, 7);
+
+        assert_eq!(
+            Pubkey::create_program_address(
+                &[b"vault", creator.as_ref(), &7u64.to_le_bytes(), &[bump]],
+                &ID
+            ),
+            Ok(vault)
+        );
+        assert_ne!(vault, vault_pda(&creator, 8).0);
+        assert_ne!(vault, vault_pda(&Pubkey::new_unique(), 7).0);
+        assert_ne!(vault, vault_state_pda(&creator, 7).0);
+    }
+}
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum VaultError {
+    #[msg("vault already exists")]
+    VaultAlreadyExists,
+    #[msg("invalid amount")]
+    InvalidAmount,
+    #[msg("insufficient funds")]
+    InsufficientFunds,
+    #[msg("remaining balance below rent minimum")]
+    BelowRentMinimum,
+    #[msg("vault is locked")]
+    VaultLocked,
+    #[msg("invalid unlock time")]
+    InvalidUnlockTime,
+    #[msg("guardian approval required")]
+    GuardianApprovalRequired,
+    #[msg("invalid guardian config")]
+    InvalidGuardianConfig,
+    #[msg("not a guardian")]
+    NotAGuardian,
+    #[msg("already approved")]
+    AlreadyApproved,
+    #[msg("not enough approvals")]
+    NotEnoughApprovals,
+    #[msg("delegation expired")]
+    DelegationExpired,
+    #[msg("allowance exceeded")]
+    AllowanceExceeded,
+    #[msg("invalid spend policy")]
+    InvalidSpendPolicy,
+    #[msg("rate limit exceeded")]
+    RateLimitExceeded,
+    #[msg("invalid beneficiary")]
+    InvalidBeneficiary,
+    #[msg("owner still active")]
+    OwnerStillActive,
+    #[msg("too many vaults")]
+    TooManyVaults,
+    #[msg("vault not empty")]
+    VaultNotEmpty,
+    #[msg("unauthorized")]
+    Unauthorized,
+    #[msg("invalid pending owner")]
+    InvalidPendingOwner,
+    #[msg("funds are staked")]
+    FundsStaked,
+    #[msg("stake account in use")]
+    StakeAccountInUse,
+    #[msg("withdraw queue required")]
+    WithdrawQueueRequired,
+    #[msg("withdraw not ready")]
+    WithdrawNotReady,
+    #[msg("invalid queue policy")]
+    InvalidQueuePolicy,
+    #[msg("destination not allowed")]
+    DestinationNotAllowed,
+    #[msg("invalid destination")]
+    InvalidDestination,
+    #[msg("vault is frozen")]
+    VaultFrozen,
+    #[msg("vault is not frozen")]
+    VaultNotFrozen,
+    #[msg("too many delegates")]
+    TooManyDelegates,
+    #[msg("too many token mints")]
+    TooManyTokenMints,
+    #[msg("delegation was revoked")]
+    DelegationRevoked,
+    #[msg("withdraw request is for a different asset")]
+    RequestMintMismatch,
+}
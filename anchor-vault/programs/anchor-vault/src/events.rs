@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct VaultDeposited {
+    pub owner: Pubkey,
+    pub vault: Pubkey,
+    pub amount: u64,
+    pub balance: u64, // vault lamports after the deposit
+    pub slot: u64,
+}
+
+#[event]
+pub struct VaultWithdrawn {
+    pub owner: Pubkey,
+    pub vault: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub balance: u64, // vault lamports after the withdrawal
+    pub staked: u64,  // lamports still delegated, not part of balance
+    pub slot: u64,
+}
+
+#[event]
+pub struct TokenDeposited {
+    pub owner: Pubkey,
+    pub vault: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub balance: u64, // vault token account balance after the deposit, net of fees
+    pub slot: u64,
+}
+
+#[event]
+pub struct TokenWithdrawn {
+    pub owner: Pubkey,
+    pub vault: Pubkey,
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub balance: u64, // vault token account balance after the withdrawal
+    pub slot: u64,
+}
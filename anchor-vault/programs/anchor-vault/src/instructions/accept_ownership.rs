@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::VaultError,
+    state::{VaultRegistry, VaultState},
+};
+
+#[derive(Accounts)]
+pub struct AcceptOwnership<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump,
+        constraint = vault_state.pending_owner == Some(signer.key()) @ VaultError::InvalidPendingOwner
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"registry", vault_state.owner.as_ref()],
+        bump = previous_registry.bump
+    )]
+    pub previous_registry: Account<'info, VaultRegistry>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = VaultRegistry::INIT_SPACE + VaultRegistry::DISCRIMINATOR.len(),
+        seeds = [b"registry", signer.key().as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, VaultRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<AcceptOwnership>) -> Result<()> {
+    let vault = ctx.accounts.vault_state.registered();
+
+    // the vault follows its owner from one registry to the other
+    ctx.accounts.previous_registry.unregister(vault);
+    ctx.accounts
+        .registry
+        .register(ctx.accounts.signer.key(), vault, ctx.bumps.registry)?;
+
+    let vault_state = &mut ctx.accounts.vault_state;
+    vault_state.owner = ctx.accounts.signer.key();
+    vault_state.pending_owner = None;
+
+    // whoever the old owner let spend from or inherit the vault doesn't carry
+    // over, stale delegation accounts stay behind for revoke_delegate to close
+    vault_state.delegates.clear();
+    vault_state.beneficiary = None;
+    vault_state.pending_beneficiary_at = 0;
+
+    vault_state.touch()
+}
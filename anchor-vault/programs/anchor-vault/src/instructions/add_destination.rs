@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::VaultError,
+    state::{AllowedDestination, VaultState, DESTINATION_ADD_DELAY, MAX_DESTINATIONS},
+};
+
+#[derive(Accounts)]
+pub struct AddDestination<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump,
+        constraint = vault_state.owner == signer.key() @ VaultError::Unauthorized
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+pub fn handler(ctx: Context<AddDestination>, destination: Pubkey) -> Result<()> {
+    let vault_state = &mut ctx.accounts.vault_state;
+
+    require!(
+        !vault_state
+            .allowlist
+            .iter()
+            .any(|allowed| allowed.address == destination),
+        VaultError::InvalidDestination
+    );
+    require_gt!(
+        MAX_DESTINATIONS,
+        vault_state.allowlist.len(),
+        VaultError::InvalidDestination
+    );
+
+    // new destinations only become payable after a delay
+    vault_state.allowlist.push(AllowedDestination {
+        address: destination,
+        active_at: Clock::get()?.unix_timestamp + DESTINATION_ADD_DELAY,
+    });
+
+    vault_state.touch()
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::VaultError,
+    state::{Delegation, VaultState},
+};
+
+#[derive(Accounts)]
+pub struct ApproveDelegate<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump,
+        constraint = vault_state.owner == signer.key() @ VaultError::Unauthorized
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        seeds = [
+            b"vault",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: only used as the delegation key
+    pub delegate: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = Delegation::INIT_SPACE + Delegation::DISCRIMINATOR.len(),
+        seeds = [b"delegate", vault.key().as_ref(), delegate.key().as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<ApproveDelegate>, allowance: u64, expires_at: i64) -> Result<()> {
+    require_gt!(allowance, 0, VaultError::InvalidAmount);
+
+    ctx.accounts.delegation.set_inner(Delegation {
+        vault: ctx.accounts.vault.key(),
+        delegate: ctx.accounts.delegate.key(),
+        allowance,
+        expires_at,
+        bump: ctx.bumps.delegation,
+    });
+
+    let vault_state = &mut ctx.accounts.vault_state;
+    vault_state.add_delegate(ctx.accounts.delegate.key())?;
+
+    vault_state.touch()
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::VaultError,
+    state::{VaultState, WithdrawProposal},
+};
+
+#[derive(Accounts)]
+pub struct ApproveWithdraw<'info> {
+    pub guardian: Signer<'info>,
+
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"proposal",
+            vault_state.key().as_ref(),
+            proposal.id.to_le_bytes().as_ref()
+        ],
+        bump = proposal.bump,
+        has_one = vault_state
+    )]
+    pub proposal: Account<'info, WithdrawProposal>,
+}
+
+pub fn handler(ctx: Context<ApproveWithdraw>) -> Result<()> {
+    let guardian = ctx.accounts.guardian.key();
+
+    require!(
+        ctx.accounts.vault_state.is_guardian(&guardian),
+        VaultError::NotAGuardian
+    );
+
+    let vault_state = &ctx.accounts.vault_state;
+    let proposal = &mut ctx.accounts.proposal;
+
+    require!(
+        !proposal.approvals.contains(&guardian),
+        VaultError::AlreadyApproved
+    );
+
+    // approvals of guardians removed since don't count, and left in place
+    // they'd outgrow the space allocated for MAX_GUARDIANS approvals
+    proposal
+        .approvals
+        .retain(|approved| vault_state.is_guardian(approved));
+    proposal.approvals.push(guardian);
+
+    Ok(())
+}
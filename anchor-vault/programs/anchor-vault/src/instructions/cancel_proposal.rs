@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::VaultError,
+    state::{VaultState, WithdrawProposal},
+};
+
+#[derive(Accounts)]
+pub struct CancelProposal<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump,
+        constraint = vault_state.owner == signer.key() @ VaultError::Unauthorized
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        close = signer,
+        seeds = [
+            b"proposal",
+            vault_state.key().as_ref(),
+            proposal.id.to_le_bytes().as_ref()
+        ],
+        bump = proposal.bump,
+        has_one = vault_state
+    )]
+    pub proposal: Account<'info, WithdrawProposal>,
+}
+
+pub fn handler(ctx: Context<CancelProposal>) -> Result<()> {
+    ctx.accounts.vault_state.open_proposals -= 1;
+
+    ctx.accounts.vault_state.touch()
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::VaultError,
+    state::{VaultState, WithdrawRequest},
+};
+
+#[derive(Accounts)]
+pub struct CancelWithdraw<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump,
+        constraint = vault_state.owner == signer.key() @ VaultError::Unauthorized
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        close = signer,
+        seeds = [
+            b"request",
+            vault_state.key().as_ref(),
+            request.id.to_le_bytes().as_ref()
+        ],
+        bump = request.bump,
+        has_one = vault_state
+    )]
+    pub request: Account<'info, WithdrawRequest>,
+}
+
+pub fn handler(ctx: Context<CancelWithdraw>) -> Result<()> {
+    ctx.accounts.vault_state.open_requests -= 1;
+
+    ctx.accounts.vault_state.touch()
+}
@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::VaultWithdrawn,
+    instructions::{check_withdraw_amount, transfer_from_vault},
+    state::VaultState,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimInheritance<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vault",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<ClaimInheritance>, amount: u64) -> Result<()> {
+    let beneficiary = ctx.accounts.beneficiary.key();
+    ctx.accounts.vault_state.check_claimable(&beneficiary)?;
+
+    // no touch here, a claim isn't owner activity
+    ctx.accounts.vault_state.check_not_frozen()?;
+    ctx.accounts.vault_state.check_unlocked()?;
+
+    // check_claimable already waited out the queue cooldown, which stands in
+    // for check_direct_withdraw as the beneficiary can't queue. guardians still
+    // co-sign and the spend limit still applies, so a beneficiary set with a
+    // leaked owner key can't empty the vault in one go
+    if !ctx.accounts.vault_state.guardians.is_empty() {
+        ctx.accounts
+            .vault_state
+            .check_guardian_signers(ctx.remaining_accounts)?;
+    }
+
+    check_withdraw_amount(
+        ctx.accounts.vault.lamports(),
+        ctx.accounts.vault_state.staked,
+        amount,
+    )?;
+
+    ctx.accounts.vault_state.record_spend(amount)?;
+
+    transfer_from_vault(
+        &ctx.accounts.system_program,
+        &ctx.accounts.vault,
+        ctx.accounts.beneficiary.to_account_info(),
+        &ctx.accounts.vault_state.creator,
+        ctx.accounts.vault_state.vault_id,
+        ctx.bumps.vault,
+        amount,
+    )?;
+
+    emit_cpi!(VaultWithdrawn {
+        owner: ctx.accounts.vault_state.owner,
+        vault: ctx.accounts.vault.key(),
+        destination: beneficiary,
+        amount,
+        balance: ctx.accounts.vault.lamports(),
+        staked: ctx.accounts.vault_state.staked,
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{errors::VaultError, events::TokenWithdrawn, state::VaultState};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimInheritanceToken<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        seeds = [
+            b"vault",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    // token accounts
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = beneficiary,
+        associated_token::mint = mint,
+        associated_token::authority = beneficiary,
+        associated_token::token_program = token_program
+    )]
+    pub beneficiary_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vault,
+        associated_token::token_program = token_program
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // programs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ClaimInheritanceToken<'info> {
+    pub fn claim(&self, amount: u64, bump: u8) -> Result<()> {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.vault_ata.to_account_info(),
+                    to: self.beneficiary_ata.to_account_info(),
+                    authority: self.vault.to_account_info(),
+                    mint: self.mint.to_account_info(),
+                },
+                &[&[
+                    b"vault",
+                    self.vault_state.creator.as_ref(),
+                    self.vault_state.vault_id.to_le_bytes().as_ref(),
+                    &[bump],
+                ]],
+            ),
+            amount,
+            self.mint.decimals,
+        )?;
+
+        Ok(())
+    }
+}
+
+pub fn handler(ctx: Context<ClaimInheritanceToken>, amount: u64) -> Result<()> {
+    let beneficiary = ctx.accounts.beneficiary.key();
+    ctx.accounts.vault_state.check_claimable(&beneficiary)?;
+
+    ctx.accounts.vault_state.check_not_frozen()?;
+    ctx.accounts.vault_state.check_unlocked()?;
+
+    // the queue cooldown is covered by check_claimable, and like withdraw_token
+    // the lamport spend limit can't meter tokens, so guardians co-sign
+    if !ctx.accounts.vault_state.guardians.is_empty() {
+        ctx.accounts
+            .vault_state
+            .check_guardian_signers(ctx.remaining_accounts)?;
+    }
+
+    require_gt!(amount, 0, VaultError::InvalidAmount);
+    require_gte!(
+        ctx.accounts.vault_ata.amount,
+        amount,
+        VaultError::InsufficientFunds
+    );
+
+    ctx.accounts.claim(amount, ctx.bumps.vault)?;
+    ctx.accounts.vault_ata.reload()?;
+
+    let (mint, balance) = (ctx.accounts.mint.key(), ctx.accounts.vault_ata.amount);
+    ctx.accounts.vault_state.release_token_mint(&mint, balance);
+
+    emit_cpi!(TokenWithdrawn {
+        owner: ctx.accounts.vault_state.owner,
+        vault: ctx.accounts.vault.key(),
+        mint: ctx.accounts.mint.key(),
+        destination: ctx.accounts.beneficiary.key(),
+        amount,
+        balance: ctx.accounts.vault_ata.amount,
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
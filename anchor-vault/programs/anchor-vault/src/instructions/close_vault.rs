@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::VaultError,
+    state::{VaultRegistry, VaultState},
+};
+
+#[derive(Accounts)]
+pub struct CloseVault<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        close = signer,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump,
+        constraint = vault_state.owner == signer.key() @ VaultError::Unauthorized
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        seeds = [
+            b"vault",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"registry", signer.key().as_ref()],
+        bump = registry.bump
+    )]
+    pub registry: Account<'info, VaultRegistry>,
+}
+
+pub fn handler(ctx: Context<CloseVault>) -> Result<()> {
+    require_eq!(ctx.accounts.vault.lamports(), 0, VaultError::VaultNotEmpty);
+    require_eq!(
+        ctx.accounts.vault_state.staked,
+        0,
+        VaultError::VaultNotEmpty
+    );
+
+    let vault_state = &ctx.accounts.vault_state;
+    vault_state.check_closable()?;
+
+    // closing resets the vault config, so it must not be a way around it
+    vault_state.check_not_frozen()?;
+    vault_state.check_unlocked()?;
+    require!(
+        vault_state.guardians.is_empty(),
+        VaultError::GuardianApprovalRequired
+    );
+
+    ctx.accounts.registry.unregister(vault_state.registered());
+
+    Ok(())
+}
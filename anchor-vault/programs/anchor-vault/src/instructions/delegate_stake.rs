@@ -0,0 +1,179 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    program::{invoke, invoke_signed},
+    stake::{
+        self,
+        instruction::{delegate_stake, initialize},
+        state::{Authorized, Lockup, StakeStateV2},
+    },
+    system_instruction::{allocate, assign, transfer},
+    system_program,
+};
+use anchor_spl::stake::Stake;
+
+use crate::{errors::VaultError, instructions::check_withdraw_amount, state::VaultState};
+
+#[derive(Accounts)]
+pub struct DelegateVaultStake<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump,
+        constraint = vault_state.owner == signer.key() @ VaultError::Unauthorized
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vault",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: created and initialized here through the stake program
+    #[account(
+        mut,
+        seeds = [b"stake", vault.key().as_ref()],
+        bump
+    )]
+    pub stake_account: UncheckedAccount<'info>,
+
+    /// CHECK: validated by the stake program on delegation
+    pub vote_account: UncheckedAccount<'info>,
+
+    /// CHECK: stake config, unused by the stake program but still required
+    #[account(address = stake::config::ID)]
+    pub stake_config: UncheckedAccount<'info>,
+
+    // sysvars
+    pub clock: Sysvar<'info, Clock>,
+    pub rent: Sysvar<'info, Rent>,
+    pub stake_history: Sysvar<'info, StakeHistory>,
+
+    // programs
+    pub stake_program: Program<'info, Stake>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> DelegateVaultStake<'info> {
+    pub fn create_and_delegate(&self, amount: u64, bumps: &DelegateVaultStakeBumps) -> Result<()> {
+        let vault_id = self.vault_state.vault_id.to_le_bytes();
+        let vault_key = self.vault.key();
+
+        let vault_seeds: &[&[u8]] = &[
+            b"vault",
+            self.vault_state.creator.as_ref(),
+            vault_id.as_ref(),
+            &[bumps.vault],
+        ];
+        let stake_seeds: &[&[u8]] = &[b"stake", vault_key.as_ref(), &[bumps.stake_account]];
+
+        // not create_account, which fails once anyone has sent the address a
+        // lamport. the vault funds the stake account and stays both staker
+        // and withdrawer, whatever was sent there before gets staked along
+        invoke_signed(
+            &transfer(&vault_key, &self.stake_account.key(), amount),
+            &[
+                self.vault.to_account_info(),
+                self.stake_account.to_account_info(),
+                self.system_program.to_account_info(),
+            ],
+            &[vault_seeds],
+        )?;
+
+        invoke_signed(
+            &allocate(&self.stake_account.key(), StakeStateV2::size_of() as u64),
+            &[
+                self.stake_account.to_account_info(),
+                self.system_program.to_account_info(),
+            ],
+            &[stake_seeds],
+        )?;
+
+        invoke_signed(
+            &assign(&self.stake_account.key(), &stake::program::ID),
+            &[
+                self.stake_account.to_account_info(),
+                self.system_program.to_account_info(),
+            ],
+            &[stake_seeds],
+        )?;
+
+        invoke(
+            &initialize(
+                &self.stake_account.key(),
+                &Authorized {
+                    staker: vault_key,
+                    withdrawer: vault_key,
+                },
+                &Lockup::default(),
+            ),
+            &[
+                self.stake_account.to_account_info(),
+                self.rent.to_account_info(),
+                self.stake_program.to_account_info(),
+            ],
+        )?;
+
+        invoke_signed(
+            &delegate_stake(
+                &self.stake_account.key(),
+                &vault_key,
+                &self.vote_account.key(),
+            ),
+            &[
+                self.stake_account.to_account_info(),
+                self.vote_account.to_account_info(),
+                self.clock.to_account_info(),
+                self.stake_history.to_account_info(),
+                self.stake_config.to_account_info(),
+                self.vault.to_account_info(),
+                self.stake_program.to_account_info(),
+            ],
+            &[vault_seeds],
+        )?;
+
+        Ok(())
+    }
+}
+
+pub fn handler(ctx: Context<DelegateVaultStake>, amount: u64) -> Result<()> {
+    // one stake account per vault, withdraw_stake has to empty it first,
+    // which hands the address back to the system program
+    require_keys_eq!(
+        *ctx.accounts.stake_account.owner,
+        system_program::ID,
+        VaultError::StakeAccountInUse
+    );
+
+    // staked lamports are out of reach of the lock and freeze checks until
+    // they come back, so only stake what could be withdrawn right now
+    ctx.accounts.vault_state.check_not_frozen()?;
+    ctx.accounts.vault_state.check_unlocked()?;
+
+    // an emptied vault reads as closed to deposit and deposit_locked, keep
+    // the rent minimum behind while the rest is staked
+    let balance = ctx.accounts.vault.lamports();
+    require_gt!(balance, amount, VaultError::InsufficientFunds);
+    check_withdraw_amount(balance, ctx.accounts.vault_state.staked, amount)?;
+
+    ctx.accounts.create_and_delegate(amount, &ctx.bumps)?;
+
+    // lamports already sitting at the stake address were delegated as well
+    let staked = ctx.accounts.stake_account.lamports();
+    let vault_state = &mut ctx.accounts.vault_state;
+    vault_state.staked = staked;
+
+    vault_state.touch()
+}
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::VaultError,
+    events::VaultWithdrawn,
+    instructions::{check_withdraw_amount, transfer_from_vault},
+    state::{Delegation, VaultState},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DelegateWithdraw<'info> {
+    #[account(mut)]
+    pub delegate: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vault",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"delegate", vault.key().as_ref(), delegate.key().as_ref()],
+        bump = delegation.bump,
+        has_one = vault,
+        has_one = delegate,
+        constraint = vault_state.delegates.contains(&delegate.key()) @ VaultError::DelegationRevoked
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<DelegateWithdraw>, amount: u64) -> Result<()> {
+    ctx.accounts.vault_state.check_not_frozen()?;
+    ctx.accounts.vault_state.check_unlocked()?;
+    ctx.accounts.vault_state.check_direct_withdraw()?;
+    ctx.accounts.vault_state.check_guardian_limit(amount)?;
+    ctx.accounts.delegation.check_not_expired()?;
+
+    require_gte!(
+        ctx.accounts.delegation.allowance,
+        amount,
+        VaultError::AllowanceExceeded
+    );
+
+    check_withdraw_amount(
+        ctx.accounts.vault.lamports(),
+        ctx.accounts.vault_state.staked,
+        amount,
+    )?;
+
+    ctx.accounts.vault_state.record_spend(amount)?;
+
+    ctx.accounts.delegation.allowance -= amount;
+
+    transfer_from_vault(
+        &ctx.accounts.system_program,
+        &ctx.accounts.vault,
+        ctx.accounts.delegate.to_account_info(),
+        &ctx.accounts.vault_state.creator,
+        ctx.accounts.vault_state.vault_id,
+        ctx.bumps.vault,
+        amount,
+    )?;
+
+    emit_cpi!(VaultWithdrawn {
+        owner: ctx.accounts.vault_state.owner,
+        vault: ctx.accounts.vault.key(),
+        destination: ctx.accounts.delegate.key(),
+        amount,
+        balance: ctx.accounts.vault.lamports(),
+        staked: ctx.accounts.vault_state.staked,
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
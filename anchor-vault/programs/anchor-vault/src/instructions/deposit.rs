@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+
+use crate::{events::VaultDeposited, instructions::VaultAction};
+
+pub fn handler(ctx: Context<VaultAction>, vault_id: u64, amount: u64) -> Result<()> {
+    ctx.accounts.open(vault_id, &ctx.bumps)?;
+    ctx.accounts.check_first_deposit(amount)?;
+
+    ctx.accounts.vault_state.touch()?;
+
+    ctx.accounts.deposit_lamports(amount)?;
+
+    emit_cpi!(VaultDeposited {
+        owner: ctx.accounts.vault_state.owner,
+        vault: ctx.accounts.vault.key(),
+        amount,
+        balance: ctx.accounts.vault.lamports(),
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::VaultError, events::VaultDeposited, instructions::VaultAction};
+
+pub fn handler(
+    ctx: Context<VaultAction>,
+    vault_id: u64,
+    amount: u64,
+    unlock_at: i64,
+) -> Result<()> {
+    ctx.accounts.open(vault_id, &ctx.bumps)?;
+    ctx.accounts.check_first_deposit(amount)?;
+
+    require_gt!(
+        unlock_at,
+        Clock::get()?.unix_timestamp,
+        VaultError::InvalidUnlockTime
+    );
+
+    // an empty vault isn't necessarily unlocked, reopening it can only ever
+    // push the unlock time later, same as extend_lock
+    let vault_state = &mut ctx.accounts.vault_state;
+    require_gte!(
+        unlock_at,
+        vault_state.unlock_at,
+        VaultError::InvalidUnlockTime
+    );
+
+    vault_state.unlock_at = unlock_at;
+    vault_state.touch()?;
+
+    ctx.accounts.deposit_lamports(amount)?;
+
+    emit_cpi!(VaultDeposited {
+        owner: ctx.accounts.vault_state.owner,
+        vault: ctx.accounts.vault.key(),
+        amount,
+        balance: ctx.accounts.vault.lamports(),
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,133 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    errors::VaultError,
+    events::TokenDeposited,
+    state::{VaultRegistry, VaultState},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_id: u64)]
+pub struct DepositToken<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: key the vault was created under, only used as a seed
+    pub creator: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [
+            b"vault",
+            creator.key().as_ref(),
+            vault_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = VaultState::INIT_SPACE + VaultState::DISCRIMINATOR.len(),
+        seeds = [
+            b"state",
+            creator.key().as_ref(),
+            vault_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = VaultRegistry::INIT_SPACE + VaultRegistry::DISCRIMINATOR.len(),
+        seeds = [b"registry", signer.key().as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, VaultRegistry>,
+
+    // token accounts
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub signer_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = mint,
+        associated_token::authority = vault,
+        associated_token::token_program = token_program
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // programs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> DepositToken<'info> {
+    pub fn deposit(&self, amount: u64) -> Result<()> {
+        transfer_checked(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.signer_ata.to_account_info(),
+                    to: self.vault_ata.to_account_info(),
+                    authority: self.signer.to_account_info(),
+                    mint: self.mint.to_account_info(),
+                },
+            ),
+            amount,
+            self.mint.decimals,
+        )?;
+
+        Ok(())
+    }
+}
+
+pub fn handler(ctx: Context<DepositToken>, vault_id: u64, amount: u64) -> Result<()> {
+    require_gt!(amount, 0, VaultError::InvalidAmount);
+
+    ctx.accounts.vault_state.open(
+        ctx.accounts.signer.key(),
+        ctx.accounts.creator.key(),
+        vault_id,
+        ctx.bumps.vault_state,
+    )?;
+    let vault = ctx.accounts.vault_state.registered();
+    ctx.accounts
+        .registry
+        .register(ctx.accounts.signer.key(), vault, ctx.bumps.registry)?;
+    ctx.accounts.vault_state.touch()?;
+
+    let mint = ctx.accounts.mint.key();
+    ctx.accounts.vault_state.add_token_mint(mint)?;
+
+    ctx.accounts.deposit(amount)?;
+    ctx.accounts.vault_ata.reload()?;
+
+    emit_cpi!(TokenDeposited {
+        owner: ctx.accounts.vault_state.owner,
+        vault: ctx.accounts.vault.key(),
+        mint: ctx.accounts.mint.key(),
+        amount,
+        balance: ctx.accounts.vault_ata.amount,
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::VaultError,
+    events::VaultWithdrawn,
+    instructions::{check_withdraw_amount, transfer_from_vault},
+    state::{VaultState, WithdrawProposal},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteWithdraw<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump,
+        constraint = vault_state.owner == signer.key() @ VaultError::Unauthorized
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vault",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = signer,
+        seeds = [
+            b"proposal",
+            vault_state.key().as_ref(),
+            proposal.id.to_le_bytes().as_ref()
+        ],
+        bump = proposal.bump,
+        has_one = vault_state
+    )]
+    pub proposal: Account<'info, WithdrawProposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ExecuteWithdraw<'info> {
+    pub fn check_approvals(&self) -> Result<()> {
+        // only count guardians that are still registered
+        let approvals = self
+            .proposal
+            .approvals
+            .iter()
+            .filter(|guardian| self.vault_state.is_guardian(guardian))
+            .count();
+
+        require_gte!(
+            approvals,
+            self.vault_state.approvals_required as usize,
+            VaultError::NotEnoughApprovals
+        );
+
+        Ok(())
+    }
+}
+
+pub fn handler(ctx: Context<ExecuteWithdraw>) -> Result<()> {
+    ctx.accounts.vault_state.check_not_frozen()?;
+    ctx.accounts.vault_state.check_unlocked()?;
+    ctx.accounts.vault_state.touch()?;
+
+    ctx.accounts.check_approvals()?;
+
+    // a proposal is queued like a request, so it waits out the cooldown
+    // instead of going through check_direct_withdraw
+    require_gte!(
+        Clock::get()?.unix_timestamp,
+        ctx.accounts.proposal.ready_at,
+        VaultError::WithdrawNotReady
+    );
+
+    let amount = ctx.accounts.proposal.amount;
+
+    check_withdraw_amount(
+        ctx.accounts.vault.lamports(),
+        ctx.accounts.vault_state.staked,
+        amount,
+    )?;
+
+    ctx.accounts.vault_state.record_spend(amount)?;
+    ctx.accounts.vault_state.open_proposals -= 1;
+
+    transfer_from_vault(
+        &ctx.accounts.system_program,
+        &ctx.accounts.vault,
+        ctx.accounts.signer.to_account_info(),
+        &ctx.accounts.vault_state.creator,
+        ctx.accounts.vault_state.vault_id,
+        ctx.bumps.vault,
+        amount,
+    )?;
+
+    emit_cpi!(VaultWithdrawn {
+        owner: ctx.accounts.vault_state.owner,
+        vault: ctx.accounts.vault.key(),
+        destination: ctx.accounts.signer.key(),
+        amount,
+        balance: ctx.accounts.vault.lamports(),
+        staked: ctx.accounts.vault_state.staked,
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::VaultError,
+    events::VaultWithdrawn,
+    instructions::{check_withdraw_amount, transfer_from_vault},
+    state::{VaultState, WithdrawRequest},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FinalizeWithdraw<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump,
+        constraint = vault_state.owner == signer.key() @ VaultError::Unauthorized
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vault",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = signer,
+        seeds = [
+            b"request",
+            vault_state.key().as_ref(),
+            request.id.to_le_bytes().as_ref()
+        ],
+        bump = request.bump,
+        has_one = vault_state,
+        constraint = request.mint.is_none() @ VaultError::RequestMintMismatch
+    )]
+    pub request: Account<'info, WithdrawRequest>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<FinalizeWithdraw>) -> Result<()> {
+    require_gte!(
+        Clock::get()?.unix_timestamp,
+        ctx.accounts.request.ready_at,
+        VaultError::WithdrawNotReady
+    );
+
+    let amount = ctx.accounts.request.amount;
+
+    ctx.accounts.vault_state.check_not_frozen()?;
+    ctx.accounts.vault_state.check_unlocked()?;
+    ctx.accounts.vault_state.touch()?;
+    ctx.accounts.vault_state.check_guardian_limit(amount)?;
+
+    check_withdraw_amount(
+        ctx.accounts.vault.lamports(),
+        ctx.accounts.vault_state.staked,
+        amount,
+    )?;
+
+    ctx.accounts.vault_state.record_spend(amount)?;
+    ctx.accounts.vault_state.open_requests -= 1;
+
+    transfer_from_vault(
+        &ctx.accounts.system_program,
+        &ctx.accounts.vault,
+        ctx.accounts.signer.to_account_info(),
+        &ctx.accounts.vault_state.creator,
+        ctx.accounts.vault_state.vault_id,
+        ctx.bumps.vault,
+        amount,
+    )?;
+
+    emit_cpi!(VaultWithdrawn {
+        owner: ctx.accounts.vault_state.owner,
+        vault: ctx.accounts.vault.key(),
+        destination: ctx.accounts.signer.key(),
+        amount,
+        balance: ctx.accounts.vault.lamports(),
+        staked: ctx.accounts.vault_state.staked,
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
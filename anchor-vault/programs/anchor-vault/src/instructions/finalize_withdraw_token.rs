@@ -0,0 +1,152 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    errors::VaultError,
+    events::TokenWithdrawn,
+    state::{VaultState, WithdrawRequest},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FinalizeWithdrawToken<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump,
+        constraint = vault_state.owner == signer.key() @ VaultError::Unauthorized
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        seeds = [
+            b"vault",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = signer,
+        seeds = [
+            b"request",
+            vault_state.key().as_ref(),
+            request.id.to_le_bytes().as_ref()
+        ],
+        bump = request.bump,
+        has_one = vault_state,
+        constraint = request.mint == Some(mint.key()) @ VaultError::RequestMintMismatch
+    )]
+    pub request: Account<'info, WithdrawRequest>,
+
+    // token accounts
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program
+    )]
+    pub signer_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vault,
+        associated_token::token_program = token_program
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // programs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> FinalizeWithdrawToken<'info> {
+    pub fn withdraw(&self, amount: u64, bump: u8) -> Result<()> {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.vault_ata.to_account_info(),
+                    to: self.signer_ata.to_account_info(),
+                    authority: self.vault.to_account_info(),
+                    mint: self.mint.to_account_info(),
+                },
+                &[&[
+                    b"vault",
+                    self.vault_state.creator.as_ref(),
+                    self.vault_state.vault_id.to_le_bytes().as_ref(),
+                    &[bump],
+                ]],
+            ),
+            amount,
+            self.mint.decimals,
+        )?;
+
+        Ok(())
+    }
+}
+
+pub fn handler(ctx: Context<FinalizeWithdrawToken>) -> Result<()> {
+    require_gte!(
+        Clock::get()?.unix_timestamp,
+        ctx.accounts.request.ready_at,
+        VaultError::WithdrawNotReady
+    );
+
+    let amount = ctx.accounts.request.amount;
+
+    ctx.accounts.vault_state.check_not_frozen()?;
+    ctx.accounts.vault_state.check_unlocked()?;
+    ctx.accounts.vault_state.touch()?;
+
+    // same as withdraw_token, guardians co-sign every token withdrawal
+    if !ctx.accounts.vault_state.guardians.is_empty() {
+        ctx.accounts
+            .vault_state
+            .check_guardian_signers(ctx.remaining_accounts)?;
+    }
+
+    require_gte!(
+        ctx.accounts.vault_ata.amount,
+        amount,
+        VaultError::InsufficientFunds
+    );
+
+    ctx.accounts.withdraw(amount, ctx.bumps.vault)?;
+    ctx.accounts.vault_ata.reload()?;
+
+    let (mint, balance) = (ctx.accounts.mint.key(), ctx.accounts.vault_ata.amount);
+    ctx.accounts.vault_state.release_token_mint(&mint, balance);
+    ctx.accounts.vault_state.open_requests -= 1;
+
+    emit_cpi!(TokenWithdrawn {
+        owner: ctx.accounts.vault_state.owner,
+        vault: ctx.accounts.vault.key(),
+        mint: ctx.accounts.mint.key(),
+        destination: ctx.accounts.signer.key(),
+        amount,
+        balance: ctx.accounts.vault_ata.amount,
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
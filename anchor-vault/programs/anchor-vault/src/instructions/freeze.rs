@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::VaultError, state::VaultState};
+
+#[derive(Accounts)]
+pub struct Freeze<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump,
+        constraint = vault_state.owner == signer.key()
+            || vault_state.is_guardian(&signer.key()) @ VaultError::Unauthorized
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+pub fn handler(ctx: Context<Freeze>) -> Result<()> {
+    let signer = ctx.accounts.signer.key();
+    let vault_state = &mut ctx.accounts.vault_state;
+    vault_state.frozen = true;
+
+    // freezing again also cancels a pending owner unfreeze
+    vault_state.unfreeze_at = 0;
+
+    if vault_state.owner == signer {
+        vault_state.touch()?;
+    }
+
+    Ok(())
+}
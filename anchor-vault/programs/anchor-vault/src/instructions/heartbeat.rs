@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::VaultError, state::VaultState};
+
+#[derive(Accounts)]
+pub struct Heartbeat<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump,
+        constraint = vault_state.owner == signer.key() @ VaultError::Unauthorized
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+pub fn handler(ctx: Context<Heartbeat>) -> Result<()> {
+    ctx.accounts.vault_state.touch()
+}
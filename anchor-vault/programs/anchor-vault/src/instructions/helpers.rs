@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+use crate::errors::VaultError;
+
+pub fn transfer_from_vault<'info>(
+    system_program: &Program<'info, System>,
+    vault: &SystemAccount<'info>,
+    to: AccountInfo<'info>,
+    creator: &Pubkey,
+    vault_id: u64,
+    bump: u8,
+    amount: u64,
+) -> Result<()> {
+    transfer(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Transfer {
+                from: vault.to_account_info(),
+                to,
+            },
+            &[&[
+                b"vault",
+                creator.as_ref(),
+                vault_id.to_le_bytes().as_ref(),
+                &[bump], // must be a array of u8's
+            ]],
+        ),
+        amount,
+    )?;
+
+    Ok(())
+}
+
+pub fn check_withdraw_amount(balance: u64, staked: u64, amount: u64) -> Result<()> {
+    check_payout(balance, staked, amount, Rent::get()?.minimum_balance(0))
+}
+
+fn check_payout(balance: u64, staked: u64, amount: u64, rent_minimum: u64) -> Result<()> {
+    require_gt!(amount, 0, VaultError::InvalidAmount);
+
+    // only liquid lamports can be paid out, say so if the rest is staked
+    if amount > balance && amount <= balance.saturating_add(staked) {
+        return err!(VaultError::FundsStaked);
+    }
+    require_gte!(balance, amount, VaultError::InsufficientFunds);
+
+    // either drain the vault completely or leave it rent exempt
+    let remaining = balance - amount;
+    if remaining > 0 {
+        require_gte!(remaining, rent_minimum, VaultError::BelowRentMinimum);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RENT: u64 = 890_880;
+
+    fn assert_error(result: Result<()>, expected: VaultError) {
+        match result.unwrap_err() {
+            Error::AnchorError(error) => assert_eq!(error.error_code_number, u32::from(expected)),
+            Error::ProgramError(error) => panic!("unexpected {error}"),
+        }
+    }
+
+    #[test]
+    fn payout_drains_or_leaves_rent() {
+        assert!(check_payout(1_000_000, 0, 1_000_000, RENT).is_ok());
+        assert!(check_payout(1_000_000 + RENT, 0, 1_000_000, RENT).is_ok());
+
+        assert_error(
+            check_payout(1_000_000, 0, 1_000_000 - 1, RENT),
+            VaultError::BelowRentMinimum,
+        );
+    }
+
+    #[test]
+    fn payout_rejects_zero_and_overdraws() {
+        assert_error(
+            check_payout(1_000_000, 0, 0, RENT),
+            VaultError::InvalidAmount,
+        );
+        assert_error(
+            check_payout(1_000_000, 0, 1_000_001, RENT),
+            VaultError::InsufficientFunds,
+        );
+    }
+
+    #[test]
+    fn payout_points_at_staked_funds() {
+        assert_error(
+            check_payout(1_000_000, 500_000, 1_500_000, RENT),
+            VaultError::FundsStaked,
+        );
+        assert_error(
+            check_payout(1_000_000, 500_000, 1_500_001, RENT),
+            VaultError::InsufficientFunds,
+        );
+    }
+}
@@ -0,0 +1,81 @@
+pub mod helpers;
+pub use helpers::*;
+pub mod vault_action;
+pub use vault_action::*;
+pub mod deposit;
+pub mod deposit_locked;
+pub mod extend_lock;
+pub mod partial_withdraw;
+pub mod top_up;
+pub mod withdraw;
+pub use extend_lock::*;
+pub mod set_guardians;
+pub use set_guardians::*;
+pub mod propose_withdraw;
+pub use propose_withdraw::*;
+pub mod approve_withdraw;
+pub use approve_withdraw::*;
+pub mod execute_withdraw;
+pub use execute_withdraw::*;
+pub mod cancel_proposal;
+pub use cancel_proposal::*;
+pub mod deposit_token;
+pub use deposit_token::*;
+pub mod withdraw_token;
+pub use withdraw_token::*;
+pub mod approve_delegate;
+pub use approve_delegate::*;
+pub mod set_delegate_allowance;
+pub use set_delegate_allowance::*;
+pub mod revoke_delegate;
+pub use revoke_delegate::*;
+pub mod delegate_withdraw;
+pub use delegate_withdraw::*;
+pub mod set_spend_policy;
+pub use set_spend_policy::*;
+pub mod heartbeat;
+pub use heartbeat::*;
+pub mod set_beneficiary;
+pub use set_beneficiary::*;
+pub mod claim_inheritance;
+pub use claim_inheritance::*;
+pub mod claim_inheritance_token;
+pub use claim_inheritance_token::*;
+pub mod close_vault;
+pub use close_vault::*;
+pub mod propose_owner;
+pub use propose_owner::*;
+pub mod accept_ownership;
+pub use accept_ownership::*;
+pub mod delegate_stake;
+pub use delegate_stake::*;
+pub mod deactivate_stake;
+pub use deactivate_stake::*;
+pub mod withdraw_stake;
+pub use withdraw_stake::*;
+pub mod set_queue_policy;
+pub use set_queue_policy::*;
+pub mod request_withdraw;
+pub use request_withdraw::*;
+pub mod finalize_withdraw;
+pub use finalize_withdraw::*;
+pub mod cancel_withdraw;
+pub use cancel_withdraw::*;
+pub mod request_withdraw_token;
+pub use request_withdraw_token::*;
+pub mod finalize_withdraw_token;
+pub use finalize_withdraw_token::*;
+pub mod add_destination;
+pub use add_destination::*;
+pub mod remove_destination;
+pub use remove_destination::*;
+pub mod withdraw_to;
+pub use withdraw_to::*;
+pub mod freeze;
+pub use freeze::*;
+pub mod request_unfreeze;
+pub use request_unfreeze::*;
+pub mod unfreeze;
+pub use unfreeze::*;
+pub mod withdraw_legacy;
+pub use withdraw_legacy::*;
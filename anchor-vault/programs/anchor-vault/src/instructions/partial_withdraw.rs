@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::VaultWithdrawn,
+    instructions::{check_withdraw_amount, VaultAction},
+};
+
+pub fn handler(ctx: Context<VaultAction>, vault_id: u64, amount: u64) -> Result<()> {
+    ctx.accounts.open(vault_id, &ctx.bumps)?;
+    ctx.accounts.vault_state.check_not_frozen()?;
+    ctx.accounts.vault_state.check_unlocked()?;
+    ctx.accounts.vault_state.check_direct_withdraw()?;
+    ctx.accounts.vault_state.touch()?;
+    ctx.accounts.vault_state.check_guardian_limit(amount)?;
+
+    check_withdraw_amount(
+        ctx.accounts.vault.lamports(),
+        ctx.accounts.vault_state.staked,
+        amount,
+    )?;
+
+    ctx.accounts.vault_state.record_spend(amount)?;
+
+    ctx.accounts.withdraw_lamports(amount, ctx.bumps.vault)?;
+
+    emit_cpi!(VaultWithdrawn {
+        owner: ctx.accounts.vault_state.owner,
+        vault: ctx.accounts.vault.key(),
+        destination: ctx.accounts.signer.key(),
+        amount,
+        balance: ctx.accounts.vault.lamports(),
+        staked: ctx.accounts.vault_state.staked,
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
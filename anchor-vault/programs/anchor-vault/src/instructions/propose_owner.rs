@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::VaultError, state::VaultState};
+
+#[derive(Accounts)]
+pub struct ProposeOwner<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump,
+        constraint = vault_state.owner == signer.key() @ VaultError::Unauthorized
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+// passing None cancels a pending transfer
+pub fn handler(ctx: Context<ProposeOwner>, new_owner: Option<Pubkey>) -> Result<()> {
+    if let Some(new_owner) = new_owner {
+        require_keys_neq!(
+            new_owner,
+            ctx.accounts.signer.key(),
+            VaultError::InvalidPendingOwner
+        );
+    }
+
+    let vault_state = &mut ctx.accounts.vault_state;
+    vault_state.pending_owner = new_owner;
+
+    vault_state.touch()
+}
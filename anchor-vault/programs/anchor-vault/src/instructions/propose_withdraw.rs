@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::VaultError,
+    state::{VaultState, WithdrawProposal},
+};
+
+#[derive(Accounts)]
+pub struct ProposeWithdraw<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump,
+        constraint = vault_state.owner == signer.key() @ VaultError::Unauthorized
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = WithdrawProposal::INIT_SPACE + WithdrawProposal::DISCRIMINATOR.len(),
+        seeds = [
+            b"proposal",
+            vault_state.key().as_ref(),
+            vault_state.proposal_count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub proposal: Account<'info, WithdrawProposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<ProposeWithdraw>, amount: u64) -> Result<()> {
+    require_gt!(amount, 0, VaultError::InvalidAmount);
+
+    let now = Clock::get()?.unix_timestamp;
+    let vault_state = &mut ctx.accounts.vault_state;
+
+    vault_state.apply_pending_queue_policy(now);
+
+    ctx.accounts.proposal.set_inner(WithdrawProposal {
+        vault_state: vault_state.key(),
+        id: vault_state.proposal_count,
+        amount,
+        approvals: Vec::new(),
+        ready_at: now + vault_state.queue_policy.cooldown,
+        bump: ctx.bumps.proposal,
+    });
+
+    vault_state.proposal_count += 1;
+    vault_state.open_proposals += 1;
+
+    vault_state.touch()
+}
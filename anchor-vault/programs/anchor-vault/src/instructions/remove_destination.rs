@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::VaultError, state::VaultState};
+
+#[derive(Accounts)]
+pub struct RemoveDestination<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump,
+        constraint = vault_state.owner == signer.key() @ VaultError::Unauthorized
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+pub fn handler(ctx: Context<RemoveDestination>, destination: Pubkey) -> Result<()> {
+    let vault_state = &mut ctx.accounts.vault_state;
+
+    let len = vault_state.allowlist.len();
+    vault_state
+        .allowlist
+        .retain(|allowed| allowed.address != destination);
+
+    require_neq!(
+        vault_state.allowlist.len(),
+        len,
+        VaultError::InvalidDestination
+    );
+
+    vault_state.touch()
+}
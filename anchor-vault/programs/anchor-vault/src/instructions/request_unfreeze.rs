@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::VaultError,
+    state::{VaultState, UNFREEZE_DELAY},
+};
+
+#[derive(Accounts)]
+pub struct RequestUnfreeze<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump,
+        constraint = vault_state.owner == signer.key() @ VaultError::Unauthorized
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+pub fn handler(ctx: Context<RequestUnfreeze>) -> Result<()> {
+    let vault_state = &mut ctx.accounts.vault_state;
+
+    require!(vault_state.frozen, VaultError::VaultNotFrozen);
+
+    vault_state.unfreeze_at = Clock::get()?.unix_timestamp + UNFREEZE_DELAY;
+
+    vault_state.touch()
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    errors::VaultError,
+    state::{VaultState, WithdrawRequest},
+};
+
+#[derive(Accounts)]
+pub struct RequestWithdrawToken<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump,
+        constraint = vault_state.owner == signer.key() @ VaultError::Unauthorized
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = WithdrawRequest::INIT_SPACE + WithdrawRequest::DISCRIMINATOR.len(),
+        seeds = [
+            b"request",
+            vault_state.key().as_ref(),
+            vault_state.request_count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub request: Account<'info, WithdrawRequest>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// same queue as lamports, the request ids are shared
+pub fn handler(ctx: Context<RequestWithdrawToken>, amount: u64) -> Result<()> {
+    require_gt!(amount, 0, VaultError::InvalidAmount);
+
+    let now = Clock::get()?.unix_timestamp;
+    let vault_state = &mut ctx.accounts.vault_state;
+
+    vault_state.apply_pending_queue_policy(now);
+
+    ctx.accounts.request.set_inner(WithdrawRequest {
+        vault_state: vault_state.key(),
+        id: vault_state.request_count,
+        amount,
+        ready_at: now + vault_state.queue_policy.cooldown,
+        mint: Some(ctx.accounts.mint.key()),
+        bump: ctx.bumps.request,
+    });
+
+    vault_state.request_count += 1;
+    vault_state.open_requests += 1;
+
+    vault_state.touch()
+}
@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::VaultError,
+    state::{Delegation, VaultState},
+};
+
+#[derive(Accounts)]
+pub struct RevokeDelegate<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump,
+        constraint = vault_state.owner == signer.key() @ VaultError::Unauthorized
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        seeds = [
+            b"vault",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close = signer,
+        seeds = [b"delegate", vault.key().as_ref(), delegation.delegate.as_ref()],
+        bump = delegation.bump,
+        has_one = vault
+    )]
+    pub delegation: Account<'info, Delegation>,
+}
+
+pub fn handler(ctx: Context<RevokeDelegate>) -> Result<()> {
+    let delegate = ctx.accounts.delegation.delegate;
+
+    let vault_state = &mut ctx.accounts.vault_state;
+    vault_state.remove_delegate(&delegate);
+
+    vault_state.touch()
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::VaultError,
+    state::{VaultState, BENEFICIARY_CHANGE_DELAY, MIN_INACTIVITY_PERIOD},
+};
+
+#[derive(Accounts)]
+pub struct SetBeneficiary<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump,
+        constraint = vault_state.owner == signer.key() @ VaultError::Unauthorized
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+pub fn handler(
+    ctx: Context<SetBeneficiary>,
+    beneficiary: Option<Pubkey>,
+    inactivity_period: i64,
+) -> Result<()> {
+    if let Some(beneficiary) = beneficiary {
+        require_keys_neq!(
+            beneficiary,
+            ctx.accounts.signer.key(),
+            VaultError::InvalidBeneficiary
+        );
+        require_gte!(
+            inactivity_period,
+            MIN_INACTIVITY_PERIOD,
+            VaultError::InvalidBeneficiary
+        );
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let vault_state = &mut ctx.accounts.vault_state;
+
+    vault_state.apply_pending_beneficiary(now);
+
+    // removing the beneficiary or making the same one wait longer is safe,
+    // anything else could hand the vault to a leaked owner key, so it waits
+    let stricter = beneficiary.is_none()
+        || (beneficiary == vault_state.beneficiary
+            && inactivity_period >= vault_state.inactivity_period);
+
+    if stricter {
+        vault_state.beneficiary = beneficiary;
+        vault_state.inactivity_period = inactivity_period;
+        vault_state.pending_beneficiary_at = 0;
+    } else {
+        vault_state.pending_beneficiary = beneficiary;
+        vault_state.pending_inactivity_period = inactivity_period;
+        vault_state.pending_beneficiary_at = now + BENEFICIARY_CHANGE_DELAY;
+    }
+
+    vault_state.touch()
+}
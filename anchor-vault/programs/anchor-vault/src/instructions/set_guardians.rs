@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::VaultError,
+    state::{VaultState, MAX_GUARDIANS},
+};
+
+#[derive(Accounts)]
+pub struct SetGuardians<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump,
+        constraint = vault_state.owner == signer.key() @ VaultError::Unauthorized
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+pub fn handler(
+    ctx: Context<SetGuardians>,
+    guardians: Vec<Pubkey>,
+    approvals_required: u8,
+    guarded_above: u64,
+) -> Result<()> {
+    require_gte!(
+        MAX_GUARDIANS,
+        guardians.len(),
+        VaultError::InvalidGuardianConfig
+    );
+
+    for (i, guardian) in guardians.iter().enumerate() {
+        require!(
+            !guardians[..i].contains(guardian) && *guardian != ctx.accounts.signer.key(),
+            VaultError::InvalidGuardianConfig
+        );
+    }
+
+    // empty guardian list turns approvals off, otherwise need 1..=N approvals
+    if guardians.is_empty() {
+        require_eq!(approvals_required, 0, VaultError::InvalidGuardianConfig);
+    } else {
+        require!(
+            approvals_required > 0 && approvals_required as usize <= guardians.len(),
+            VaultError::InvalidGuardianConfig
+        );
+    }
+
+    // once guardians are set, changing them needs the current guardians too,
+    // otherwise a leaked owner key could just remove them
+    if !ctx.accounts.vault_state.guardians.is_empty() {
+        ctx.accounts
+            .vault_state
+            .check_guardian_signers(ctx.remaining_accounts)?;
+    }
+
+    let vault_state = &mut ctx.accounts.vault_state;
+    vault_state.guardians = guardians;
+    vault_state.approvals_required = approvals_required;
+    vault_state.guarded_above = guarded_above;
+
+    vault_state.touch()
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::VaultError,
+    state::{QueuePolicy, VaultState},
+};
+
+#[derive(Accounts)]
+pub struct SetQueuePolicy<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump,
+        constraint = vault_state.owner == signer.key() @ VaultError::Unauthorized
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+pub fn handler(ctx: Context<SetQueuePolicy>, required: bool, cooldown: i64) -> Result<()> {
+    require_gte!(cooldown, 0, VaultError::InvalidQueuePolicy);
+
+    let policy = QueuePolicy { required, cooldown };
+
+    let now = Clock::get()?.unix_timestamp;
+    let vault_state = &mut ctx.accounts.vault_state;
+
+    vault_state.apply_pending_queue_policy(now);
+
+    if policy.is_stricter_than(&vault_state.queue_policy) {
+        vault_state.queue_policy = policy;
+        vault_state.pending_queue_at = 0;
+    } else {
+        // loosening waits out the current cooldown, same as a queued withdrawal
+        vault_state.pending_queue_policy = policy;
+        vault_state.pending_queue_at = now + vault_state.queue_policy.cooldown;
+    }
+
+    vault_state.touch()
+}
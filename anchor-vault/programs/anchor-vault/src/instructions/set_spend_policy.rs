@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::VaultError,
+    state::{SpendPolicy, VaultState, WindowUnit, SPEND_LIMIT_RAISE_DELAY},
+};
+
+#[derive(Accounts)]
+pub struct SetSpendPolicy<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump,
+        constraint = vault_state.owner == signer.key() @ VaultError::Unauthorized
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+pub fn handler(
+    ctx: Context<SetSpendPolicy>,
+    limit: u64,
+    window_length: u64,
+    window_unit: WindowUnit,
+) -> Result<()> {
+    if limit > 0 {
+        require_gt!(window_length, 0, VaultError::InvalidSpendPolicy);
+    }
+
+    let policy = SpendPolicy {
+        limit,
+        window_length,
+        window_unit,
+    };
+
+    let clock = Clock::get()?;
+    let vault_state = &mut ctx.accounts.vault_state;
+
+    vault_state.apply_pending_policy(&clock);
+
+    if policy.is_stricter_than(&vault_state.spend_policy) {
+        // lowering the limit is safe, do it now and drop any pending raise
+        vault_state.set_spend_policy(policy);
+        vault_state.pending_policy_at = 0;
+    } else {
+        vault_state.pending_policy = policy;
+        vault_state.pending_policy_at = clock.unix_timestamp + SPEND_LIMIT_RAISE_DELAY;
+    }
+
+    vault_state.touch()
+}
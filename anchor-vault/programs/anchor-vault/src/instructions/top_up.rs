@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::VaultError, events::VaultDeposited, instructions::VaultAction};
+
+pub fn handler(ctx: Context<VaultAction>, vault_id: u64, amount: u64) -> Result<()> {
+    ctx.accounts.open(vault_id, &ctx.bumps)?;
+
+    // only the first deposit has to cover rent, later ones just add to it
+    if ctx.accounts.vault.lamports() == 0 {
+        require_gt!(
+            amount,
+            Rent::get()?.minimum_balance(0),
+            VaultError::InvalidAmount
+        );
+    } else {
+        require_gt!(amount, 0, VaultError::InvalidAmount);
+    }
+
+    ctx.accounts.vault_state.touch()?;
+
+    ctx.accounts.deposit_lamports(amount)?;
+
+    emit_cpi!(VaultDeposited {
+        owner: ctx.accounts.vault_state.owner,
+        vault: ctx.accounts.vault.key(),
+        amount,
+        balance: ctx.accounts.vault.lamports(),
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
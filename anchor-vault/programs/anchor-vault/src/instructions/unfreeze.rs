@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::{errors::VaultError, state::VaultState};
+
+#[derive(Accounts)]
+pub struct Unfreeze<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump,
+        constraint = vault_state.owner == signer.key()
+            || vault_state.is_guardian(&signer.key()) @ VaultError::Unauthorized
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+pub fn handler(ctx: Context<Unfreeze>) -> Result<()> {
+    let signer = ctx.accounts.signer.key();
+    let vault_state = &mut ctx.accounts.vault_state;
+
+    require!(vault_state.frozen, VaultError::VaultNotFrozen);
+
+    // guardians can unfreeze right away, the owner has to wait out request_unfreeze
+    if !vault_state.is_guardian(&signer) {
+        require!(vault_state.unfreeze_at != 0, VaultError::VaultFrozen);
+        require_gte!(
+            Clock::get()?.unix_timestamp,
+            vault_state.unfreeze_at,
+            VaultError::VaultFrozen
+        );
+
+        vault_state.touch()?;
+    }
+
+    vault_state.frozen = false;
+    vault_state.unfreeze_at = 0;
+
+    Ok(())
+}
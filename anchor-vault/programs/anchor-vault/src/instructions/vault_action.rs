@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+use crate::{
+    errors::VaultError,
+    instructions::transfer_from_vault,
+    state::{VaultRegistry, VaultState},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vault_id: u64)]
+pub struct VaultAction<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: key the vault was created under, only used as a seed
+    pub creator: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vault",
+            creator.key().as_ref(),
+            vault_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = VaultState::INIT_SPACE + VaultState::DISCRIMINATOR.len(),
+        seeds = [
+            b"state",
+            creator.key().as_ref(),
+            vault_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = VaultRegistry::INIT_SPACE + VaultRegistry::DISCRIMINATOR.len(),
+        // the owner's registry, and open only lets the owner through
+        seeds = [b"registry", signer.key().as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, VaultRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> VaultAction<'info> {
+    pub fn open(&mut self, vault_id: u64, bumps: &VaultActionBumps) -> Result<()> {
+        self.vault_state.open(
+            self.signer.key(),
+            self.creator.key(),
+            vault_id,
+            bumps.vault_state,
+        )?;
+
+        self.registry.register(
+            self.signer.key(),
+            self.vault_state.registered(),
+            bumps.registry,
+        )
+    }
+
+    pub fn deposit_lamports(&self, amount: u64) -> Result<()> {
+        transfer(
+            CpiContext::new(
+                self.system_program.to_account_info(),
+                Transfer {
+                    from: self.signer.to_account_info(),
+                    to: self.vault.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn withdraw_lamports(&self, amount: u64, bump: u8) -> Result<()> {
+        transfer_from_vault(
+            &self.system_program,
+            &self.vault,
+            self.signer.to_account_info(),
+            &self.vault_state.creator,
+            self.vault_state.vault_id,
+            bump,
+            amount,
+        )
+    }
+
+    pub fn check_first_deposit(&self, amount: u64) -> Result<()> {
+        require_eq!(self.vault.lamports(), 0, VaultError::VaultAlreadyExists);
+
+        // amount > rent, isliye amount is first parameter
+        require_gt!(
+            amount,
+            Rent::get()?.minimum_balance(0),
+            VaultError::InvalidAmount
+        );
+
+        Ok(())
+    }
+}
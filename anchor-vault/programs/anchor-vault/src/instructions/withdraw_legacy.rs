@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+use crate::{errors::VaultError, events::VaultWithdrawn};
+
+// vaults opened before vault ids live at [b"vault", signer] with no state
+// account, this drains one of them back to its owner
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawLegacy<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", signer.key().as_ref()],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<WithdrawLegacy>) -> Result<()> {
+    let amount = ctx.accounts.vault.lamports();
+    require_gt!(amount, 0, VaultError::InvalidAmount);
+
+    transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.signer.to_account_info(),
+            },
+            &[&[
+                b"vault",
+                ctx.accounts.signer.key().as_ref(),
+                &[ctx.bumps.vault],
+            ]],
+        ),
+        amount,
+    )?;
+
+    emit_cpi!(VaultWithdrawn {
+        owner: ctx.accounts.signer.key(),
+        vault: ctx.accounts.vault.key(),
+        destination: ctx.accounts.signer.key(),
+        amount,
+        balance: 0,
+        staked: 0,
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
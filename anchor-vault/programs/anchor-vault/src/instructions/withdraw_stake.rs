@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+use anchor_spl::stake::{withdraw, Stake, Withdraw};
+
+use crate::state::VaultState;
+
+#[derive(Accounts)]
+pub struct WithdrawVaultStake<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vault",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: owned and validated by the stake program
+    #[account(
+        mut,
+        seeds = [b"stake", vault.key().as_ref()],
+        bump
+    )]
+    pub stake_account: UncheckedAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+    pub stake_history: Sysvar<'info, StakeHistory>,
+    pub stake_program: Program<'info, Stake>,
+}
+
+pub fn handler(ctx: Context<WithdrawVaultStake>) -> Result<()> {
+    let signer = ctx.accounts.signer.key();
+    ctx.accounts.vault_state.check_owner_or_claimable(&signer)?;
+
+    // everything goes back, rewards included. the stake program rejects this
+    // until the stake is fully deactivated
+    let amount = ctx.accounts.stake_account.lamports();
+
+    withdraw(
+        CpiContext::new_with_signer(
+            ctx.accounts.stake_program.to_account_info(),
+            Withdraw {
+                stake: ctx.accounts.stake_account.to_account_info(),
+                withdrawer: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                clock: ctx.accounts.clock.to_account_info(),
+                stake_history: ctx.accounts.stake_history.to_account_info(),
+            },
+            &[&[
+                b"vault",
+                ctx.accounts.vault_state.creator.as_ref(),
+                ctx.accounts.vault_state.vault_id.to_le_bytes().as_ref(),
+                &[ctx.bumps.vault],
+            ]],
+        ),
+        amount,
+        None,
+    )?;
+
+    ctx.accounts.vault_state.staked = 0;
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::VaultError,
+    events::VaultWithdrawn,
+    instructions::{check_withdraw_amount, transfer_from_vault},
+    state::VaultState,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawTo<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"state",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump = vault_state.bump,
+        constraint = vault_state.owner == signer.key() @ VaultError::Unauthorized
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"vault",
+            vault_state.creator.as_ref(),
+            vault_state.vault_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: checked against the vault allowlist
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<WithdrawTo>, amount: u64) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .check_destination(&ctx.accounts.destination.key())?;
+
+    ctx.accounts.vault_state.check_not_frozen()?;
+    ctx.accounts.vault_state.check_unlocked()?;
+    ctx.accounts.vault_state.check_direct_withdraw()?;
+    ctx.accounts.vault_state.touch()?;
+    ctx.accounts.vault_state.check_guardian_limit(amount)?;
+
+    check_withdraw_amount(
+        ctx.accounts.vault.lamports(),
+        ctx.accounts.vault_state.staked,
+        amount,
+    )?;
+
+    ctx.accounts.vault_state.record_spend(amount)?;
+
+    transfer_from_vault(
+        &ctx.accounts.system_program,
+        &ctx.accounts.vault,
+        ctx.accounts.destination.to_account_info(),
+        &ctx.accounts.vault_state.creator,
+        ctx.accounts.vault_state.vault_id,
+        ctx.bumps.vault,
+        amount,
+    )?;
+
+    emit_cpi!(VaultWithdrawn {
+        owner: ctx.accounts.vault_state.owner,
+        vault: ctx.accounts.vault.key(),
+        destination: ctx.accounts.destination.key(),
+        amount,
+        balance: ctx.accounts.vault.lamports(),
+        staked: ctx.accounts.vault_state.staked,
+        slot: Clock::get()?.slot,
+    });
+
+    Ok(())
+}
@@ -1,5 +1,12 @@
 use anchor_lang::prelude::*;
-use anchor_lang::system_program::{transfer, Transfer};
+
+pub mod errors;
+mod events;
+mod instructions;
+pub mod state;
+
+use instructions::*;
+use state::WindowUnit;
 
 declare_id!("22222222222222222222222222222222222222222222");
 
@@ -7,74 +14,198 @@ declare_id!("22222222222222222222222222222222222222222222");
 pub mod blueshift_anchor_vault {
     use super::*;
 
-    pub fn deposit(ctx: Context<VaultAction>, amount: u64) -> Result<()> {
-        require_eq!(
-            ctx.accounts.vault.lamports(),
-            0,
-            VaultError::VaultAlreadyExists
-        );
-
-        // amount > rent, isliye amount is first parameter
-        require_gt!(
-            amount,
-            Rent::get()?.minimum_balance(0),
-            VaultError::InvalidAmount
-        );
-
-        transfer(
-            CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.signer.to_account_info(),
-                    to: ctx.accounts.vault.to_account_info(),
-                },
-            ),
-            amount,
-        )?;
-
-        Ok(())
-    }
-
-    pub fn withdraw(ctx: Context<VaultAction>) -> Result<()> {
-        transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.system_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.vault.to_account_info(),
-                    to: ctx.accounts.signer.to_account_info(),
-                },
-                &[&[
-                    b"vault",
-                    ctx.accounts.signer.key().as_ref(),
-                    &[ctx.bumps.vault], // must be a array of u8's
-                ]],
-            ),
-            ctx.accounts.vault.lamports(),
-        )?;
-
-        Ok(())
+    pub fn deposit(ctx: Context<VaultAction>, vault_id: u64, amount: u64) -> Result<()> {
+        instructions::deposit::handler(ctx, vault_id, amount)
+    }
+
+    pub fn deposit_locked(
+        ctx: Context<VaultAction>,
+        vault_id: u64,
+        amount: u64,
+        unlock_at: i64,
+    ) -> Result<()> {
+        instructions::deposit_locked::handler(ctx, vault_id, amount, unlock_at)
     }
-}
 
-#[derive(Accounts)]
-pub struct VaultAction<'info> {
-    #[account(mut)]
-    pub signer: Signer<'info>,
+    pub fn top_up(ctx: Context<VaultAction>, vault_id: u64, amount: u64) -> Result<()> {
+        instructions::top_up::handler(ctx, vault_id, amount)
+    }
 
-    #[account(
-        mut,
-        seeds = [b"vault", signer.key().as_ref()],
-        bump
-    )]
-    pub vault: SystemAccount<'info>,
+    pub fn withdraw(ctx: Context<VaultAction>, vault_id: u64) -> Result<()> {
+        instructions::withdraw::handler(ctx, vault_id)
+    }
 
-    pub system_program: Program<'info, System>,
-}
+    pub fn partial_withdraw(ctx: Context<VaultAction>, vault_id: u64, amount: u64) -> Result<()> {
+        instructions::partial_withdraw::handler(ctx, vault_id, amount)
+    }
+
+    pub fn extend_lock(ctx: Context<ExtendLock>, unlock_at: i64) -> Result<()> {
+        instructions::extend_lock::handler(ctx, unlock_at)
+    }
+
+    pub fn set_guardians(
+        ctx: Context<SetGuardians>,
+        guardians: Vec<Pubkey>,
+        approvals_required: u8,
+        guarded_above: u64,
+    ) -> Result<()> {
+        instructions::set_guardians::handler(ctx, guardians, approvals_required, guarded_above)
+    }
+
+    pub fn propose_withdraw(ctx: Context<ProposeWithdraw>, amount: u64) -> Result<()> {
+        instructions::propose_withdraw::handler(ctx, amount)
+    }
+
+    pub fn approve_withdraw(ctx: Context<ApproveWithdraw>) -> Result<()> {
+        instructions::approve_withdraw::handler(ctx)
+    }
+
+    pub fn execute_withdraw(ctx: Context<ExecuteWithdraw>) -> Result<()> {
+        instructions::execute_withdraw::handler(ctx)
+    }
+
+    pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+        instructions::cancel_proposal::handler(ctx)
+    }
+
+    pub fn deposit_token(ctx: Context<DepositToken>, vault_id: u64, amount: u64) -> Result<()> {
+        instructions::deposit_token::handler(ctx, vault_id, amount)
+    }
+
+    pub fn withdraw_token(ctx: Context<WithdrawToken>, amount: u64) -> Result<()> {
+        instructions::withdraw_token::handler(ctx, amount)
+    }
+
+    pub fn approve_delegate(
+        ctx: Context<ApproveDelegate>,
+        allowance: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::approve_delegate::handler(ctx, allowance, expires_at)
+    }
+
+    pub fn set_delegate_allowance(
+        ctx: Context<SetDelegateAllowance>,
+        allowance: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::set_delegate_allowance::handler(ctx, allowance, expires_at)
+    }
+
+    pub fn revoke_delegate(ctx: Context<RevokeDelegate>) -> Result<()> {
+        instructions::revoke_delegate::handler(ctx)
+    }
+
+    pub fn delegate_withdraw(ctx: Context<DelegateWithdraw>, amount: u64) -> Result<()> {
+        instructions::delegate_withdraw::handler(ctx, amount)
+    }
+
+    pub fn set_spend_policy(
+        ctx: Context<SetSpendPolicy>,
+        limit: u64,
+        window_length: u64,
+        window_unit: WindowUnit,
+    ) -> Result<()> {
+        instructions::set_spend_policy::handler(ctx, limit, window_length, window_unit)
+    }
+
+    pub fn heartbeat(ctx: Context<Heartbeat>) -> Result<()> {
+        instructions::heartbeat::handler(ctx)
+    }
+
+    pub fn set_beneficiary(
+        ctx: Context<SetBeneficiary>,
+        beneficiary: Option<Pubkey>,
+        inactivity_period: i64,
+    ) -> Result<()> {
+        instructions::set_beneficiary::handler(ctx, beneficiary, inactivity_period)
+    }
+
+    pub fn claim_inheritance(ctx: Context<ClaimInheritance>, amount: u64) -> Result<()> {
+        instructions::claim_inheritance::handler(ctx, amount)
+    }
+
+    pub fn close_vault(ctx: Context<CloseVault>) -> Result<()> {
+        instructions::close_vault::handler(ctx)
+    }
+
+    pub fn propose_owner(ctx: Context<ProposeOwner>, new_owner: Option<Pubkey>) -> Result<()> {
+        instructions::propose_owner::handler(ctx, new_owner)
+    }
+
+    pub fn accept_ownership(ctx: Context<AcceptOwnership>) -> Result<()> {
+        instructions::accept_ownership::handler(ctx)
+    }
+
+    pub fn delegate_stake(ctx: Context<DelegateVaultStake>, amount: u64) -> Result<()> {
+        instructions::delegate_stake::handler(ctx, amount)
+    }
+
+    pub fn deactivate_stake(ctx: Context<DeactivateVaultStake>) -> Result<()> {
+        instructions::deactivate_stake::handler(ctx)
+    }
+
+    pub fn withdraw_stake(ctx: Context<WithdrawVaultStake>) -> Result<()> {
+        instructions::withdraw_stake::handler(ctx)
+    }
+
+    pub fn set_queue_policy(
+        ctx: Context<SetQueuePolicy>,
+        required: bool,
+        cooldown: i64,
+    ) -> Result<()> {
+        instructions::set_queue_policy::handler(ctx, required, cooldown)
+    }
+
+    pub fn request_withdraw(ctx: Context<RequestWithdraw>, amount: u64) -> Result<()> {
+        instructions::request_withdraw::handler(ctx, amount)
+    }
+
+    pub fn finalize_withdraw(ctx: Context<FinalizeWithdraw>) -> Result<()> {
+        instructions::finalize_withdraw::handler(ctx)
+    }
+
+    pub fn cancel_withdraw(ctx: Context<CancelWithdraw>) -> Result<()> {
+        instructions::cancel_withdraw::handler(ctx)
+    }
+
+    pub fn request_withdraw_token(ctx: Context<RequestWithdrawToken>, amount: u64) -> Result<()> {
+        instructions::request_withdraw_token::handler(ctx, amount)
+    }
+
+    pub fn finalize_withdraw_token(ctx: Context<FinalizeWithdrawToken>) -> Result<()> {
+        instructions::finalize_withdraw_token::handler(ctx)
+    }
+
+    pub fn add_destination(ctx: Context<AddDestination>, destination: Pubkey) -> Result<()> {
+        instructions::add_destination::handler(ctx, destination)
+    }
+
+    pub fn remove_destination(ctx: Context<RemoveDestination>, destination: Pubkey) -> Result<()> {
+        instructions::remove_destination::handler(ctx, destination)
+    }
+
+    pub fn withdraw_to(ctx: Context<WithdrawTo>, amount: u64) -> Result<()> {
+        instructions::withdraw_to::handler(ctx, amount)
+    }
+
+    pub fn freeze(ctx: Context<Freeze>) -> Result<()> {
+        instructions::freeze::handler(ctx)
+    }
+
+    pub fn request_unfreeze(ctx: Context<RequestUnfreeze>) -> Result<()> {
+        instructions::request_unfreeze::handler(ctx)
+    }
 
-#[error_code]
-pub enum VaultError {
-    #[msg("vault already exists")]
-    VaultAlreadyExists,
-    #[msg("invalid amount")]
-    InvalidAmount,
+    pub fn unfreeze(ctx: Context<Unfreeze>) -> Result<()> {
+        instructions::unfreeze::handler(ctx)
+    }
+
+    pub fn claim_inheritance_token(ctx: Context<ClaimInheritanceToken>, amount: u64) -> Result<()> {
+        instructions::claim_inheritance_token::handler(ctx, amount)
+    }
+
+    pub fn withdraw_legacy(ctx: Context<WithdrawLegacy>) -> Result<()> {
+        instructions::withdraw_legacy::handler(ctx)
+    }
 }
@@ -0,0 +1,618 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::VaultError;
+
+pub const MAX_GUARDIANS: usize = 5;
+pub const MAX_VAULTS: usize = 16;
+pub const MAX_DESTINATIONS: usize = 8;
+pub const MAX_DELEGATES: usize = 8;
+pub const MAX_TOKEN_MINTS: usize = 8;
+pub const DESTINATION_ADD_DELAY: i64 = 24 * 60 * 60; // 1 day
+pub const UNFREEZE_DELAY: i64 = 24 * 60 * 60; // 1 day
+pub const SPEND_LIMIT_RAISE_DELAY: i64 = 24 * 60 * 60; // 1 day
+pub const GUARDIAN_WINDOW: i64 = 24 * 60 * 60; // 1 day
+pub const BENEFICIARY_CHANGE_DELAY: i64 = 7 * 24 * 60 * 60; // 1 week
+pub const MIN_INACTIVITY_PERIOD: i64 = 30 * 24 * 60 * 60; // 30 days
+pub const MAX_SPEND_RECORDS: usize = 16;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum WindowUnit {
+    Seconds,
+    Epochs,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct SpendPolicy {
+    pub limit: u64, // max lamports per window, 0 means no limit
+    pub window_length: u64,
+    pub window_unit: WindowUnit,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct AllowedDestination {
+    pub address: Pubkey,
+    pub active_at: i64, // unix timestamp the destination can first be paid
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct QueuePolicy {
+    pub required: bool, // direct withdrawals are rejected while set
+    pub cooldown: i64,  // seconds between request_withdraw and finalize_withdraw
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct SpendRecord {
+    pub at: u64, // in the units of the window it was recorded against
+    pub amount: u64,
+}
+
+/// Outflows inside a trailing window, oldest first.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace)]
+pub struct SpendHistory {
+    #[max_len(MAX_SPEND_RECORDS)]
+    pub records: Vec<SpendRecord>,
+}
+
+impl SpendHistory {
+    // rolling rather than fixed windows, so nothing can be doubled up by
+    // spending on both sides of a window boundary
+    pub fn record(
+        &mut self,
+        now: u64,
+        window: u64,
+        limit: u64,
+        amount: u64,
+        error: VaultError,
+    ) -> Result<()> {
+        self.records
+            .retain(|record| now.saturating_sub(record.at) < window);
+
+        let spent = self
+            .records
+            .iter()
+            .try_fold(amount, |total, record| total.checked_add(record.amount))
+            .ok_or(error)?;
+
+        require_gte!(limit, spent, error);
+
+        let full = self.records.len() == MAX_SPEND_RECORDS;
+        match self.records.last_mut() {
+            // out of slots, fold into the newest record. that keeps the amount
+            // counted for longer than needed, never shorter
+            Some(last) if full => {
+                last.at = now;
+                last.amount += amount;
+            }
+            _ => self.records.push(SpendRecord { at: now, amount }),
+        }
+
+        Ok(())
+    }
+}
+
+impl QueuePolicy {
+    pub fn is_stricter_than(&self, current: &QueuePolicy) -> bool {
+        (self.required || !current.required) && self.cooldown >= current.cooldown
+    }
+}
+
+impl SpendPolicy {
+    // stricter policies can be applied right away, anything else has to wait
+    pub fn is_stricter_than(&self, current: &SpendPolicy) -> bool {
+        if current.limit == 0 {
+            return true;
+        }
+
+        self.limit != 0
+            && self.limit <= current.limit
+            && self.window_unit == current.window_unit
+            && self.window_length >= current.window_length
+    }
+
+    pub fn window_now(&self, clock: &Clock) -> u64 {
+        match self.window_unit {
+            WindowUnit::Seconds => clock.unix_timestamp as u64,
+            WindowUnit::Epochs => clock.epoch,
+        }
+    }
+}
+
+#[derive(InitSpace)]
+#[account]
+pub struct VaultState {
+    pub creator: Pubkey, // seed key, never changes
+    pub vault_id: u64,
+    pub owner: Pubkey,
+    pub pending_owner: Option<Pubkey>,
+    pub unlock_at: i64, // unix timestamp, 0 means never locked
+    #[max_len(MAX_GUARDIANS)]
+    pub guardians: Vec<Pubkey>,
+    pub approvals_required: u8,
+    pub guarded_above: u64, // unapproved withdrawals per GUARDIAN_WINDOW, above that needs guardians
+    pub unapproved_spent: SpendHistory,
+    pub proposal_count: u64,
+    pub spend_policy: SpendPolicy,
+    pub pending_policy: SpendPolicy,
+    pub pending_policy_at: i64, // unix timestamp, 0 means nothing pending
+    pub spent: SpendHistory,    // in units of spend_policy.window_unit
+    pub beneficiary: Option<Pubkey>,
+    pub inactivity_period: i64, // seconds without owner activity before beneficiary can claim
+    pub last_active: i64,
+    pub pending_beneficiary: Option<Pubkey>,
+    pub pending_inactivity_period: i64,
+    pub pending_beneficiary_at: i64, // unix timestamp, 0 means nothing pending
+    pub staked: u64,                 // lamports moved into the vault stake account
+    pub queue_policy: QueuePolicy,
+    pub pending_queue_policy: QueuePolicy,
+    pub pending_queue_at: i64, // unix timestamp, 0 means nothing pending
+    pub request_count: u64,
+    #[max_len(MAX_DESTINATIONS)]
+    pub allowlist: Vec<AllowedDestination>,
+    pub frozen: bool,
+    pub unfreeze_at: i64, // unix timestamp, 0 means no owner unfreeze requested
+    // accounts hanging off the vault, all have to be gone before close_vault
+    #[max_len(MAX_DELEGATES)]
+    pub delegates: Vec<Pubkey>,
+    pub open_proposals: u32,
+    pub open_requests: u32,
+    #[max_len(MAX_TOKEN_MINTS)]
+    pub token_mints: Vec<Pubkey>, // deposited through deposit_token and not yet withdrawn
+    pub bump: u8,
+}
+
+impl VaultState {
+    // safe to call on every init_if_needed action, only fills in a fresh state
+    pub fn open(&mut self, signer: Pubkey, creator: Pubkey, vault_id: u64, bump: u8) -> Result<()> {
+        if self.owner == Pubkey::default() {
+            // new vaults can only be created under the signer's own key
+            require_keys_eq!(creator, signer, VaultError::Unauthorized);
+
+            self.creator = creator;
+            self.vault_id = vault_id;
+            self.owner = signer;
+            self.bump = bump;
+        }
+
+        require_keys_eq!(self.owner, signer, VaultError::Unauthorized);
+
+        Ok(())
+    }
+
+    pub fn is_guardian(&self, key: &Pubkey) -> bool {
+        self.guardians.contains(key)
+    }
+
+    pub fn check_not_frozen(&self) -> Result<()> {
+        require!(!self.frozen, VaultError::VaultFrozen);
+
+        Ok(())
+    }
+
+    pub fn check_unlocked(&self) -> Result<()> {
+        require_gte!(
+            Clock::get()?.unix_timestamp,
+            self.unlock_at,
+            VaultError::VaultLocked
+        );
+
+        Ok(())
+    }
+
+    // counted across calls, otherwise a large withdrawal could just be split
+    // into many small ones that each stay under the threshold
+    pub fn check_guardian_limit(&mut self, amount: u64) -> Result<()> {
+        if self.guardians.is_empty() {
+            return Ok(());
+        }
+
+        self.unapproved_spent.record(
+            Clock::get()?.unix_timestamp as u64,
+            GUARDIAN_WINDOW as u64,
+            self.guarded_above,
+            amount,
+            VaultError::GuardianApprovalRequired,
+        )
+    }
+
+    // guardians co-sign by being passed as signers in remaining_accounts
+    pub fn check_guardian_signers(&self, remaining_accounts: &[AccountInfo]) -> Result<()> {
+        let mut signers: Vec<Pubkey> = Vec::with_capacity(remaining_accounts.len());
+        for account in remaining_accounts {
+            if account.is_signer && self.is_guardian(account.key) && !signers.contains(account.key)
+            {
+                signers.push(*account.key);
+            }
+        }
+
+        require_gte!(
+            signers.len(),
+            self.approvals_required as usize,
+            VaultError::NotEnoughApprovals
+        );
+
+        Ok(())
+    }
+
+    pub fn check_destination(&self, destination: &Pubkey) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(
+            self.allowlist
+                .iter()
+                .any(|allowed| allowed.address == *destination && now >= allowed.active_at),
+            VaultError::DestinationNotAllowed
+        );
+
+        Ok(())
+    }
+
+    pub fn apply_pending_queue_policy(&mut self, now: i64) {
+        if self.pending_queue_at != 0 && now >= self.pending_queue_at {
+            self.queue_policy = self.pending_queue_policy;
+            self.pending_queue_at = 0;
+        }
+    }
+
+    pub fn check_direct_withdraw(&mut self) -> Result<()> {
+        self.apply_pending_queue_policy(Clock::get()?.unix_timestamp);
+
+        require!(
+            !self.queue_policy.required,
+            VaultError::WithdrawQueueRequired
+        );
+
+        Ok(())
+    }
+
+    pub fn add_delegate(&mut self, delegate: Pubkey) -> Result<()> {
+        require_gt!(
+            MAX_DELEGATES,
+            self.delegates.len(),
+            VaultError::TooManyDelegates
+        );
+
+        self.delegates.push(delegate);
+
+        Ok(())
+    }
+
+    pub fn remove_delegate(&mut self, delegate: &Pubkey) {
+        self.delegates.retain(|approved| approved != delegate);
+    }
+
+    // token accounts of the vault can't be listed on chain, so the mints are
+    // tracked from deposit until the vault's account for them is emptied
+    pub fn add_token_mint(&mut self, mint: Pubkey) -> Result<()> {
+        if !self.token_mints.contains(&mint) {
+            require_gt!(
+                MAX_TOKEN_MINTS,
+                self.token_mints.len(),
+                VaultError::TooManyTokenMints
+            );
+
+            self.token_mints.push(mint);
+        }
+
+        Ok(())
+    }
+
+    pub fn release_token_mint(&mut self, mint: &Pubkey, balance: u64) {
+        if balance == 0 {
+            self.token_mints.retain(|held| held != mint);
+        }
+    }
+
+    // a reopened vault would pick up whatever outlived the old state, and
+    // restart the proposal and request ids next to the old accounts
+    pub fn check_closable(&self) -> Result<()> {
+        require!(
+            self.delegates.is_empty()
+                && self.open_proposals == 0
+                && self.open_requests == 0
+                && self.token_mints.is_empty(),
+            VaultError::VaultNotEmpty
+        );
+
+        Ok(())
+    }
+
+    pub fn registered(&self) -> RegisteredVault {
+        RegisteredVault {
+            creator: self.creator,
+            vault_id: self.vault_id,
+        }
+    }
+
+    // called from every owner signed instruction that touches vault state
+    pub fn touch(&mut self) -> Result<()> {
+        self.last_active = Clock::get()?.unix_timestamp;
+
+        Ok(())
+    }
+
+    pub fn apply_pending_beneficiary(&mut self, now: i64) {
+        if self.pending_beneficiary_at != 0 && now >= self.pending_beneficiary_at {
+            self.beneficiary = self.pending_beneficiary;
+            self.inactivity_period = self.pending_inactivity_period;
+            self.pending_beneficiary_at = 0;
+        }
+    }
+
+    // the owner has to stay quiet for the inactivity period, and at least for
+    // the queue cooldown, which a direct claim would otherwise get around
+    pub fn check_claimable(&mut self, beneficiary: &Pubkey) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+
+        self.apply_pending_beneficiary(now);
+        self.apply_pending_queue_policy(now);
+
+        require!(
+            self.beneficiary == Some(*beneficiary),
+            VaultError::InvalidBeneficiary
+        );
+
+        let claimable_at = self
+            .last_active
+            .saturating_add(self.inactivity_period.max(self.queue_policy.cooldown));
+
+        require_gte!(now, claimable_at, VaultError::OwnerStillActive);
+
+        Ok(())
+    }
+
+    // the beneficiary can unwind the stake once the vault is claimable, so the
+    // lamports land back in the vault where claim_inheritance reaches them
+    pub fn check_owner_or_claimable(&mut self, signer: &Pubkey) -> Result<()> {
+        if *signer == self.owner {
+            return self.touch();
+        }
+
+        self.check_claimable(signer)
+    }
+
+    pub fn set_spend_policy(&mut self, policy: SpendPolicy) {
+        // records are timestamped in window units, they can't carry over
+        if policy.window_unit != self.spend_policy.window_unit {
+            self.spent = SpendHistory::default();
+        }
+
+        self.spend_policy = policy;
+    }
+
+    pub fn apply_pending_policy(&mut self, clock: &Clock) {
+        if self.pending_policy_at != 0 && clock.unix_timestamp >= self.pending_policy_at {
+            self.set_spend_policy(self.pending_policy);
+            self.pending_policy_at = 0;
+        }
+    }
+
+    // every lamport outflow goes through here so the window budget is shared.
+    // the window trails the current time, it doesn't reset on fixed boundaries
+    pub fn record_spend(&mut self, amount: u64) -> Result<()> {
+        let clock = Clock::get()?;
+
+        self.apply_pending_policy(&clock);
+
+        let policy = self.spend_policy;
+        if policy.limit == 0 {
+            return Ok(());
+        }
+
+        self.spent.record(
+            policy.window_now(&clock),
+            policy.window_length,
+            policy.limit,
+            amount,
+            VaultError::RateLimitExceeded,
+        )
+    }
+}
+
+#[derive(InitSpace)]
+#[account]
+pub struct VaultRegistry {
+    pub owner: Pubkey,
+    #[max_len(MAX_VAULTS)]
+    pub vaults: Vec<RegisteredVault>,
+    pub bump: u8,
+}
+
+// vault ids are only unique per creator, and transferred vaults land in
+// registries next to vaults of other creators
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub struct RegisteredVault {
+    pub creator: Pubkey,
+    pub vault_id: u64,
+}
+
+impl VaultRegistry {
+    pub fn register(&mut self, owner: Pubkey, vault: RegisteredVault, bump: u8) -> Result<()> {
+        self.owner = owner;
+        self.bump = bump;
+
+        if !self.vaults.contains(&vault) {
+            require_gt!(MAX_VAULTS, self.vaults.len(), VaultError::TooManyVaults);
+
+            self.vaults.push(vault);
+        }
+
+        Ok(())
+    }
+
+    pub fn unregister(&mut self, vault: RegisteredVault) {
+        self.vaults.retain(|registered| *registered != vault);
+    }
+}
+
+#[derive(InitSpace)]
+#[account]
+pub struct WithdrawProposal {
+    pub vault_state: Pubkey,
+    pub id: u64,
+    pub amount: u64,
+    #[max_len(MAX_GUARDIANS)]
+    pub approvals: Vec<Pubkey>,
+    pub ready_at: i64, // queue cooldown applies from the proposal, like a request
+    pub bump: u8,
+}
+
+#[derive(InitSpace)]
+#[account]
+pub struct Delegation {
+    pub vault: Pubkey,
+    pub delegate: Pubkey,
+    pub allowance: u64,
+    pub expires_at: i64, // unix timestamp, 0 means no expiry
+    pub bump: u8,
+}
+
+impl Delegation {
+    pub fn check_not_expired(&self) -> Result<()> {
+        if self.expires_at != 0 {
+            require_gt!(
+                self.expires_at,
+                Clock::get()?.unix_timestamp,
+                VaultError::DelegationExpired
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(InitSpace)]
+#[account]
+pub struct WithdrawRequest {
+    pub vault_state: Pubkey,
+    pub id: u64,
+    pub amount: u64,
+    pub ready_at: i64,
+    pub mint: Option<Pubkey>, // None for lamports
+    pub bump: u8,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_error(result: Result<()>, expected: VaultError) {
+        match result.unwrap_err() {
+            Error::AnchorError(error) => assert_eq!(error.error_code_number, u32::from(expected)),
+            Error::ProgramError(error) => panic!("unexpected {error}"),
+        }
+    }
+
+    fn record(history: &mut SpendHistory, now: u64, amount: u64) -> Result<()> {
+        // 100 per 10 units
+        history.record(now, 10, 100, amount, VaultError::RateLimitExceeded)
+    }
+
+    #[test]
+    fn record_sums_inside_the_window() {
+        let mut history = SpendHistory::default();
+
+        record(&mut history, 0, 60).unwrap();
+        record(&mut history, 5, 40).unwrap();
+
+        assert_error(record(&mut history, 9, 1), VaultError::RateLimitExceeded);
+    }
+
+    #[test]
+    fn record_forgets_spends_once_they_leave_the_window() {
+        let mut history = SpendHistory::default();
+
+        record(&mut history, 0, 60).unwrap();
+        record(&mut history, 5, 40).unwrap();
+        // the spend at 0 is out, the one at 5 still counts
+        record(&mut history, 10, 60).unwrap();
+
+        assert_error(record(&mut history, 14, 1), VaultError::RateLimitExceeded);
+        record(&mut history, 15, 40).unwrap();
+    }
+
+    #[test]
+    fn record_has_no_window_boundary_to_spend_across() {
+        let mut history = SpendHistory::default();
+
+        // fixed windows of 10 would reset at 10 and allow another 100
+        record(&mut history, 9, 100).unwrap();
+
+        assert_error(record(&mut history, 10, 100), VaultError::RateLimitExceeded);
+    }
+
+    #[test]
+    fn record_folds_into_the_newest_record_when_full() {
+        let mut history = SpendHistory::default();
+        for at in 0..MAX_SPEND_RECORDS as u64 {
+            history
+                .record(at, 100, 1_000, 1, VaultError::RateLimitExceeded)
+                .unwrap();
+        }
+
+        history
+            .record(50, 100, 1_000, 5, VaultError::RateLimitExceeded)
+            .unwrap();
+
+        assert_eq!(history.records.len(), MAX_SPEND_RECORDS);
+        let last = history.records.last().unwrap();
+        assert_eq!((last.at, last.amount), (50, 6));
+    }
+
+    #[test]
+    fn record_rejects_overflowing_amounts() {
+        let mut history = SpendHistory::default();
+        history
+            .record(0, 10, u64::MAX, 1, VaultError::RateLimitExceeded)
+            .unwrap();
+
+        assert_error(
+            history.record(0, 10, u64::MAX, u64::MAX, VaultError::RateLimitExceeded),
+            VaultError::RateLimitExceeded,
+        );
+    }
+
+    fn spend_policy(limit: u64, window_length: u64, window_unit: WindowUnit) -> SpendPolicy {
+        SpendPolicy {
+            limit,
+            window_length,
+            window_unit,
+        }
+    }
+
+    #[test]
+    fn spend_policy_stricter_only_if_it_allows_less() {
+        let current = spend_policy(100, 10, WindowUnit::Seconds);
+
+        assert!(spend_policy(50, 10, WindowUnit::Seconds).is_stricter_than(&current));
+        assert!(spend_policy(100, 20, WindowUnit::Seconds).is_stricter_than(&current));
+
+        assert!(!spend_policy(200, 10, WindowUnit::Seconds).is_stricter_than(&current));
+        assert!(!spend_policy(100, 5, WindowUnit::Seconds).is_stricter_than(&current));
+        assert!(!spend_policy(0, 10, WindowUnit::Seconds).is_stricter_than(&current));
+        assert!(!spend_policy(50, 10, WindowUnit::Epochs).is_stricter_than(&current));
+    }
+
+    #[test]
+    fn any_spend_policy_is_stricter_than_none() {
+        let current = spend_policy(0, 0, WindowUnit::Seconds);
+
+        assert!(spend_policy(u64::MAX, 1, WindowUnit::Epochs).is_stricter_than(&current));
+        assert!(spend_policy(0, 0, WindowUnit::Seconds).is_stricter_than(&current));
+    }
+
+    #[test]
+    fn queue_policy_stricter_only_if_it_waits_longer() {
+        let current = QueuePolicy {
+            required: false,
+            cooldown: 60,
+        };
+        let policy = |required, cooldown| QueuePolicy { required, cooldown };
+
+        assert!(policy(true, 60).is_stricter_than(&current));
+        assert!(policy(false, 120).is_stricter_than(&current));
+        assert!(!policy(true, 30).is_stricter_than(&current));
+
+        let required = policy(true, 60);
+        assert!(!policy(false, 60).is_stricter_than(&required));
+    }
+}
@@ -0,0 +1,1936 @@
+// needs the sbf build of the program, `cargo test-sbf` builds it and turns
+// this feature on. `anchor run test-sbf` does the same
+#![cfg(feature = "test-sbf")]
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program_pack::Pack,
+        stake, system_instruction, sysvar,
+    },
+    system_program, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{
+        self, get_associated_token_address_with_program_id,
+        spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+    },
+    token::spl_token,
+    token_2022::spl_token_2022::{
+        self,
+        extension::StateWithExtensions,
+        instruction::{initialize_mint2, mint_to},
+        state::{Account as TokenAccount, Mint},
+    },
+};
+use anchor_vault::{
+    errors::VaultError,
+    state::{WindowUnit, DESTINATION_ADD_DELAY, MIN_INACTIVITY_PERIOD, UNFREEZE_DELAY},
+};
+use litesvm::LiteSVM;
+use solana_sdk::{
+    clock::Clock,
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+// cargo test-sbf points SBF_OUT_DIR at its output, anchor build uses target/deploy
+fn program_so() -> PathBuf {
+    env::var_os("SBF_OUT_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy"))
+        .join("anchor_vault.so")
+}
+
+fn setup() -> (LiteSVM, Keypair) {
+    let mut svm = LiteSVM::new();
+    svm.add_program_from_file(anchor_vault::ID, program_so())
+        .expect("program not built, run `cargo build-sbf` or `anchor build`");
+
+    let owner = Keypair::new();
+    svm.airdrop(&owner.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+
+    (svm, owner)
+}
+
+fn now(svm: &LiteSVM) -> i64 {
+    svm.get_sysvar::<Clock>().unix_timestamp
+}
+
+fn warp_to(svm: &mut LiteSVM, unix_timestamp: i64) {
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = unix_timestamp;
+    svm.set_sysvar(&clock);
+}
+
+fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &anchor_vault::ID).0
+}
+
+fn vault_action(
+    signer: &Pubkey,
+    creator: &Pubkey,
+    vault_id: u64,
+) -> anchor_vault::accounts::VaultAction {
+    let id = vault_id.to_le_bytes();
+
+    anchor_vault::accounts::VaultAction {
+        signer: *signer,
+        creator: *creator,
+        vault: pda(&[b"vault", creator.as_ref(), &id]),
+        vault_state: pda(&[b"state", creator.as_ref(), &id]),
+        registry: pda(&[b"registry", signer.as_ref()]),
+        system_program: system_program::ID,
+        event_authority: pda(&[b"__event_authority"]),
+        program: anchor_vault::ID,
+    }
+}
+
+fn create_mint(svm: &mut LiteSVM, authority: &Keypair, token_program: &Pubkey) -> Pubkey {
+    let mint = Keypair::new();
+
+    let create = system_instruction::create_account(
+        &authority.pubkey(),
+        &mint.pubkey(),
+        svm.minimum_balance_for_rent_exemption(Mint::LEN),
+        Mint::LEN as u64,
+        token_program,
+    );
+    let initialize =
+        initialize_mint2(token_program, &mint.pubkey(), &authority.pubkey(), None, 6).unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[create, initialize],
+        Some(&authority.pubkey()),
+        &[authority, &mint],
+        svm.latest_blockhash(),
+    );
+    svm.send_transaction(tx).unwrap();
+
+    mint.pubkey()
+}
+
+fn mint_tokens(
+    svm: &mut LiteSVM,
+    authority: &Keypair,
+    mint: &Pubkey,
+    owner: &Pubkey,
+    token_program: &Pubkey,
+    amount: u64,
+) {
+    let create =
+        create_associated_token_account_idempotent(&authority.pubkey(), owner, mint, token_program);
+    let mint_to = mint_to(
+        token_program,
+        mint,
+        &get_associated_token_address_with_program_id(owner, mint, token_program),
+        &authority.pubkey(),
+        &[],
+        amount,
+    )
+    .unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[create, mint_to],
+        Some(&authority.pubkey()),
+        &[authority],
+        svm.latest_blockhash(),
+    );
+    svm.send_transaction(tx).unwrap();
+}
+
+fn token_balance(svm: &LiteSVM, owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> u64 {
+    let address = get_associated_token_address_with_program_id(owner, mint, token_program);
+
+    match svm.get_account(&address) {
+        Some(account) if account.lamports > 0 => {
+            StateWithExtensions::<TokenAccount>::unpack(&account.data)
+                .unwrap()
+                .base
+                .amount
+        }
+        _ => 0,
+    }
+}
+
+fn deposit_ix(
+    accounts: anchor_vault::accounts::VaultAction,
+    vault_id: u64,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: accounts.to_account_metas(None),
+        data: anchor_vault::instruction::Deposit { vault_id, amount }.data(),
+    }
+}
+
+fn partial_withdraw_ix(
+    accounts: anchor_vault::accounts::VaultAction,
+    vault_id: u64,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: accounts.to_account_metas(None),
+        data: anchor_vault::instruction::PartialWithdraw { vault_id, amount }.data(),
+    }
+}
+
+fn top_up_ix(
+    accounts: anchor_vault::accounts::VaultAction,
+    vault_id: u64,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: accounts.to_account_metas(None),
+        data: anchor_vault::instruction::TopUp { vault_id, amount }.data(),
+    }
+}
+
+fn deposit_locked_ix(
+    accounts: anchor_vault::accounts::VaultAction,
+    vault_id: u64,
+    amount: u64,
+    unlock_at: i64,
+) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: accounts.to_account_metas(None),
+        data: anchor_vault::instruction::DepositLocked {
+            vault_id,
+            amount,
+            unlock_at,
+        }
+        .data(),
+    }
+}
+
+fn extend_lock_ix(owner: &Pubkey, vault_state: Pubkey, unlock_at: i64) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::ExtendLock {
+            signer: *owner,
+            vault_state,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::ExtendLock { unlock_at }.data(),
+    }
+}
+
+// guardians co-sign through remaining accounts
+fn set_guardians_ix(
+    owner: &Pubkey,
+    vault_state: Pubkey,
+    guardians: Vec<Pubkey>,
+    approvals_required: u8,
+    guarded_above: u64,
+    co_signers: &[Pubkey],
+) -> Instruction {
+    let mut accounts = anchor_vault::accounts::SetGuardians {
+        signer: *owner,
+        vault_state,
+    }
+    .to_account_metas(None);
+    accounts.extend(
+        co_signers
+            .iter()
+            .map(|guardian| AccountMeta::new_readonly(*guardian, true)),
+    );
+
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts,
+        data: anchor_vault::instruction::SetGuardians {
+            guardians,
+            approvals_required,
+            guarded_above,
+        }
+        .data(),
+    }
+}
+
+fn proposal_pda(vault_state: &Pubkey, id: u64) -> Pubkey {
+    pda(&[b"proposal", vault_state.as_ref(), &id.to_le_bytes()])
+}
+
+fn propose_withdraw_ix(owner: &Pubkey, vault_state: Pubkey, id: u64, amount: u64) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::ProposeWithdraw {
+            signer: *owner,
+            vault_state,
+            proposal: proposal_pda(&vault_state, id),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::ProposeWithdraw { amount }.data(),
+    }
+}
+
+fn approve_withdraw_ix(guardian: &Pubkey, vault_state: Pubkey, id: u64) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::ApproveWithdraw {
+            guardian: *guardian,
+            vault_state,
+            proposal: proposal_pda(&vault_state, id),
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::ApproveWithdraw {}.data(),
+    }
+}
+
+fn execute_withdraw_ix(
+    accounts: anchor_vault::accounts::VaultAction,
+    proposal_id: u64,
+) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::ExecuteWithdraw {
+            signer: accounts.signer,
+            vault_state: accounts.vault_state,
+            vault: accounts.vault,
+            proposal: proposal_pda(&accounts.vault_state, proposal_id),
+            system_program: system_program::ID,
+            event_authority: accounts.event_authority,
+            program: anchor_vault::ID,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::ExecuteWithdraw {}.data(),
+    }
+}
+
+fn deposit_token_ix(
+    owner: &Pubkey,
+    vault_id: u64,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let vault = vault_action(owner, owner, vault_id);
+
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::DepositToken {
+            signer: *owner,
+            creator: *owner,
+            vault: vault.vault,
+            vault_state: vault.vault_state,
+            registry: vault.registry,
+            mint: *mint,
+            signer_ata: get_associated_token_address_with_program_id(owner, mint, token_program),
+            vault_ata: get_associated_token_address_with_program_id(
+                &vault.vault,
+                mint,
+                token_program,
+            ),
+            associated_token_program: associated_token::ID,
+            token_program: *token_program,
+            system_program: system_program::ID,
+            event_authority: vault.event_authority,
+            program: anchor_vault::ID,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::DepositToken { vault_id, amount }.data(),
+    }
+}
+
+fn withdraw_token_ix(
+    owner: &Pubkey,
+    vault_id: u64,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let vault = vault_action(owner, owner, vault_id);
+
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::WithdrawToken {
+            signer: *owner,
+            vault_state: vault.vault_state,
+            vault: vault.vault,
+            mint: *mint,
+            signer_ata: get_associated_token_address_with_program_id(owner, mint, token_program),
+            vault_ata: get_associated_token_address_with_program_id(
+                &vault.vault,
+                mint,
+                token_program,
+            ),
+            associated_token_program: associated_token::ID,
+            token_program: *token_program,
+            system_program: system_program::ID,
+            event_authority: vault.event_authority,
+            program: anchor_vault::ID,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::WithdrawToken { amount }.data(),
+    }
+}
+
+fn delegation_pda(vault: &Pubkey, delegate: &Pubkey) -> Pubkey {
+    pda(&[b"delegate", vault.as_ref(), delegate.as_ref()])
+}
+
+fn approve_delegate_ix(
+    accounts: anchor_vault::accounts::VaultAction,
+    delegate: &Pubkey,
+    allowance: u64,
+    expires_at: i64,
+) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::ApproveDelegate {
+            signer: accounts.signer,
+            vault_state: accounts.vault_state,
+            vault: accounts.vault,
+            delegate: *delegate,
+            delegation: delegation_pda(&accounts.vault, delegate),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::ApproveDelegate {
+            allowance,
+            expires_at,
+        }
+        .data(),
+    }
+}
+
+fn revoke_delegate_ix(
+    accounts: anchor_vault::accounts::VaultAction,
+    delegate: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::RevokeDelegate {
+            signer: accounts.signer,
+            vault_state: accounts.vault_state,
+            vault: accounts.vault,
+            delegation: delegation_pda(&accounts.vault, delegate),
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::RevokeDelegate {}.data(),
+    }
+}
+
+fn delegate_withdraw_ix(
+    accounts: anchor_vault::accounts::VaultAction,
+    delegate: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::DelegateWithdraw {
+            delegate: *delegate,
+            vault_state: accounts.vault_state,
+            vault: accounts.vault,
+            delegation: delegation_pda(&accounts.vault, delegate),
+            system_program: system_program::ID,
+            event_authority: accounts.event_authority,
+            program: anchor_vault::ID,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::DelegateWithdraw { amount }.data(),
+    }
+}
+
+fn set_spend_policy_ix(
+    owner: &Pubkey,
+    vault_state: Pubkey,
+    limit: u64,
+    window_length: u64,
+) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::SetSpendPolicy {
+            signer: *owner,
+            vault_state,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::SetSpendPolicy {
+            limit,
+            window_length,
+            window_unit: WindowUnit::Seconds,
+        }
+        .data(),
+    }
+}
+
+fn set_beneficiary_ix(
+    owner: &Pubkey,
+    vault_state: Pubkey,
+    beneficiary: Option<Pubkey>,
+    inactivity_period: i64,
+) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::SetBeneficiary {
+            signer: *owner,
+            vault_state,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::SetBeneficiary {
+            beneficiary,
+            inactivity_period,
+        }
+        .data(),
+    }
+}
+
+fn heartbeat_ix(owner: &Pubkey, vault_state: Pubkey) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::Heartbeat {
+            signer: *owner,
+            vault_state,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::Heartbeat {}.data(),
+    }
+}
+
+fn claim_inheritance_ix(
+    accounts: anchor_vault::accounts::VaultAction,
+    beneficiary: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::ClaimInheritance {
+            beneficiary: *beneficiary,
+            vault_state: accounts.vault_state,
+            vault: accounts.vault,
+            system_program: system_program::ID,
+            event_authority: accounts.event_authority,
+            program: anchor_vault::ID,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::ClaimInheritance { amount }.data(),
+    }
+}
+
+fn propose_owner_ix(owner: &Pubkey, vault_state: Pubkey, new_owner: Option<Pubkey>) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::ProposeOwner {
+            signer: *owner,
+            vault_state,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::ProposeOwner { new_owner }.data(),
+    }
+}
+
+fn accept_ownership_ix(
+    new_owner: &Pubkey,
+    previous_owner: &Pubkey,
+    vault_state: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::AcceptOwnership {
+            signer: *new_owner,
+            vault_state,
+            previous_registry: pda(&[b"registry", previous_owner.as_ref()]),
+            registry: pda(&[b"registry", new_owner.as_ref()]),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::AcceptOwnership {}.data(),
+    }
+}
+
+fn delegate_stake_ix(
+    accounts: anchor_vault::accounts::VaultAction,
+    vote_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::DelegateVaultStake {
+            signer: accounts.signer,
+            vault_state: accounts.vault_state,
+            vault: accounts.vault,
+            stake_account: pda(&[b"stake", accounts.vault.as_ref()]),
+            vote_account: *vote_account,
+            stake_config: stake::config::ID,
+            clock: sysvar::clock::ID,
+            rent: sysvar::rent::ID,
+            stake_history: sysvar::stake_history::ID,
+            stake_program: stake::program::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::DelegateStake { amount }.data(),
+    }
+}
+
+fn set_queue_policy_ix(
+    owner: &Pubkey,
+    vault_state: Pubkey,
+    required: bool,
+    cooldown: i64,
+) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::SetQueuePolicy {
+            signer: *owner,
+            vault_state,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::SetQueuePolicy { required, cooldown }.data(),
+    }
+}
+
+fn request_pda(vault_state: &Pubkey, id: u64) -> Pubkey {
+    pda(&[b"request", vault_state.as_ref(), &id.to_le_bytes()])
+}
+
+fn request_withdraw_ix(owner: &Pubkey, vault_state: Pubkey, id: u64, amount: u64) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::RequestWithdraw {
+            signer: *owner,
+            vault_state,
+            request: request_pda(&vault_state, id),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::RequestWithdraw { amount }.data(),
+    }
+}
+
+fn request_withdraw_token_ix(
+    owner: &Pubkey,
+    vault_state: Pubkey,
+    id: u64,
+    mint: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::RequestWithdrawToken {
+            signer: *owner,
+            vault_state,
+            mint: *mint,
+            request: request_pda(&vault_state, id),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::RequestWithdrawToken { amount }.data(),
+    }
+}
+
+fn finalize_withdraw_ix(accounts: anchor_vault::accounts::VaultAction, id: u64) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::FinalizeWithdraw {
+            signer: accounts.signer,
+            vault_state: accounts.vault_state,
+            vault: accounts.vault,
+            request: request_pda(&accounts.vault_state, id),
+            system_program: system_program::ID,
+            event_authority: accounts.event_authority,
+            program: anchor_vault::ID,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::FinalizeWithdraw {}.data(),
+    }
+}
+
+fn finalize_withdraw_token_ix(
+    accounts: anchor_vault::accounts::VaultAction,
+    id: u64,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::FinalizeWithdrawToken {
+            signer: accounts.signer,
+            vault_state: accounts.vault_state,
+            vault: accounts.vault,
+            request: request_pda(&accounts.vault_state, id),
+            mint: *mint,
+            signer_ata: get_associated_token_address_with_program_id(
+                &accounts.signer,
+                mint,
+                token_program,
+            ),
+            vault_ata: get_associated_token_address_with_program_id(
+                &accounts.vault,
+                mint,
+                token_program,
+            ),
+            associated_token_program: associated_token::ID,
+            token_program: *token_program,
+            system_program: system_program::ID,
+            event_authority: accounts.event_authority,
+            program: anchor_vault::ID,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::FinalizeWithdrawToken {}.data(),
+    }
+}
+
+fn add_destination_ix(owner: &Pubkey, vault_state: Pubkey, destination: Pubkey) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::AddDestination {
+            signer: *owner,
+            vault_state,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::AddDestination { destination }.data(),
+    }
+}
+
+fn remove_destination_ix(owner: &Pubkey, vault_state: Pubkey, destination: Pubkey) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::RemoveDestination {
+            signer: *owner,
+            vault_state,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::RemoveDestination { destination }.data(),
+    }
+}
+
+fn withdraw_to_ix(
+    accounts: anchor_vault::accounts::VaultAction,
+    destination: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::WithdrawTo {
+            signer: accounts.signer,
+            vault_state: accounts.vault_state,
+            vault: accounts.vault,
+            destination: *destination,
+            system_program: system_program::ID,
+            event_authority: accounts.event_authority,
+            program: anchor_vault::ID,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::WithdrawTo { amount }.data(),
+    }
+}
+
+fn freeze_ix(signer: &Pubkey, vault_state: Pubkey) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::Freeze {
+            signer: *signer,
+            vault_state,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::Freeze {}.data(),
+    }
+}
+
+fn request_unfreeze_ix(owner: &Pubkey, vault_state: Pubkey) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::RequestUnfreeze {
+            signer: *owner,
+            vault_state,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::RequestUnfreeze {}.data(),
+    }
+}
+
+fn unfreeze_ix(signer: &Pubkey, vault_state: Pubkey) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::Unfreeze {
+            signer: *signer,
+            vault_state,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::Unfreeze {}.data(),
+    }
+}
+
+fn cancel_proposal_ix(owner: &Pubkey, vault_state: Pubkey, id: u64) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::CancelProposal {
+            signer: *owner,
+            vault_state,
+            proposal: proposal_pda(&vault_state, id),
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::CancelProposal {}.data(),
+    }
+}
+
+fn close_vault_ix(accounts: anchor_vault::accounts::VaultAction) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::CloseVault {
+            signer: accounts.signer,
+            vault_state: accounts.vault_state,
+            vault: accounts.vault,
+            registry: accounts.registry,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::CloseVault {}.data(),
+    }
+}
+
+fn withdraw_legacy_ix(owner: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: anchor_vault::accounts::WithdrawLegacy {
+            signer: *owner,
+            vault: pda(&[b"vault", owner.as_ref()]),
+            system_program: system_program::ID,
+            event_authority: pda(&[b"__event_authority"]),
+            program: anchor_vault::ID,
+        }
+        .to_account_metas(None),
+        data: anchor_vault::instruction::WithdrawLegacy {}.data(),
+    }
+}
+
+fn withdraw_ix(accounts: anchor_vault::accounts::VaultAction, vault_id: u64) -> Instruction {
+    Instruction {
+        program_id: anchor_vault::ID,
+        accounts: accounts.to_account_metas(None),
+        data: anchor_vault::instruction::Withdraw { vault_id }.data(),
+    }
+}
+
+fn send(svm: &mut LiteSVM, ix: Instruction, signer: &Keypair) -> Result<(), TransactionError> {
+    send_signed(svm, ix, &[signer])
+}
+
+// the first signer pays
+fn send_signed(
+    svm: &mut LiteSVM,
+    ix: Instruction,
+    signers: &[&Keypair],
+) -> Result<(), TransactionError> {
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&signers[0].pubkey()),
+        signers,
+        svm.latest_blockhash(),
+    );
+
+    let result = svm
+        .send_transaction(tx)
+        .map(|_| ())
+        .map_err(|failed| failed.err);
+    // repeating an instruction would otherwise be rejected as already processed
+    svm.expire_blockhash();
+
+    result
+}
+
+fn assert_custom_error(result: Result<(), TransactionError>, code: u32) {
+    assert_eq!(
+        result,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(code)
+        ))
+    );
+}
+
+#[test]
+fn deposit() {
+    let (mut svm, owner) = setup();
+    let accounts = vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+    let vault = accounts.vault;
+
+    send(&mut svm, deposit_ix(accounts, 0, LAMPORTS_PER_SOL), &owner).unwrap();
+
+    assert_eq!(svm.get_balance(&vault), Some(LAMPORTS_PER_SOL));
+}
+
+#[test]
+fn double_deposit_fails() {
+    let (mut svm, owner) = setup();
+    let accounts = || vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+
+    send(
+        &mut svm,
+        deposit_ix(accounts(), 0, LAMPORTS_PER_SOL),
+        &owner,
+    )
+    .unwrap();
+
+    let result = send(
+        &mut svm,
+        deposit_ix(accounts(), 0, LAMPORTS_PER_SOL),
+        &owner,
+    );
+
+    assert_custom_error(result, VaultError::VaultAlreadyExists.into());
+}
+
+#[test]
+fn deposit_below_rent_minimum_fails() {
+    let (mut svm, owner) = setup();
+    let accounts = vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+    let rent = svm.minimum_balance_for_rent_exemption(0);
+
+    let result = send(&mut svm, deposit_ix(accounts, 0, rent), &owner);
+
+    assert_custom_error(result, VaultError::InvalidAmount.into());
+}
+
+#[test]
+fn withdraw() {
+    let (mut svm, owner) = setup();
+    let accounts = || vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+    let vault = accounts().vault;
+
+    send(
+        &mut svm,
+        deposit_ix(accounts(), 0, LAMPORTS_PER_SOL),
+        &owner,
+    )
+    .unwrap();
+    let before = svm.get_balance(&owner.pubkey()).unwrap();
+
+    send(&mut svm, withdraw_ix(accounts(), 0), &owner).unwrap();
+
+    assert_eq!(svm.get_balance(&vault).unwrap_or(0), 0);
+    assert!(svm.get_balance(&owner.pubkey()).unwrap() > before);
+}
+
+#[test]
+fn withdraw_with_wrong_signer_fails() {
+    let (mut svm, owner) = setup();
+    let accounts = vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+    let vault = accounts.vault;
+
+    send(&mut svm, deposit_ix(accounts, 0, LAMPORTS_PER_SOL), &owner).unwrap();
+
+    let attacker = Keypair::new();
+    svm.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+    // attacker's own seeds don't derive the owner's vault
+    let mut wrong_pda = vault_action(&attacker.pubkey(), &attacker.pubkey(), 0);
+    wrong_pda.vault = vault;
+
+    let result = send(&mut svm, withdraw_ix(wrong_pda, 0), &attacker);
+
+    assert_custom_error(
+        result,
+        anchor_lang::error::ErrorCode::ConstraintSeeds.into(),
+    );
+
+    // the owner's seeds derive it, but the attacker isn't the owner
+    let not_owner = vault_action(&attacker.pubkey(), &owner.pubkey(), 0);
+
+    let result = send(&mut svm, withdraw_ix(not_owner, 0), &attacker);
+
+    assert_custom_error(result, VaultError::Unauthorized.into());
+}
+
+#[test]
+fn partial_withdraw_leaves_the_rest() {
+    let (mut svm, owner) = setup();
+    let accounts = || vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+    let vault = accounts().vault;
+
+    send(
+        &mut svm,
+        deposit_ix(accounts(), 0, LAMPORTS_PER_SOL),
+        &owner,
+    )
+    .unwrap();
+    send(
+        &mut svm,
+        partial_withdraw_ix(accounts(), 0, LAMPORTS_PER_SOL / 4),
+        &owner,
+    )
+    .unwrap();
+
+    assert_eq!(svm.get_balance(&vault), Some(LAMPORTS_PER_SOL * 3 / 4));
+}
+
+#[test]
+fn partial_withdraw_below_rent_minimum_fails() {
+    let (mut svm, owner) = setup();
+    let accounts = || vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+
+    send(
+        &mut svm,
+        deposit_ix(accounts(), 0, LAMPORTS_PER_SOL),
+        &owner,
+    )
+    .unwrap();
+
+    // one lamport left isn't rent exempt, either take it all or leave enough
+    let result = send(
+        &mut svm,
+        partial_withdraw_ix(accounts(), 0, LAMPORTS_PER_SOL - 1),
+        &owner,
+    );
+
+    assert_custom_error(result, VaultError::BelowRentMinimum.into());
+}
+
+#[test]
+fn partial_withdraw_over_balance_fails() {
+    let (mut svm, owner) = setup();
+    let accounts = || vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+
+    send(
+        &mut svm,
+        deposit_ix(accounts(), 0, LAMPORTS_PER_SOL),
+        &owner,
+    )
+    .unwrap();
+
+    let result = send(
+        &mut svm,
+        partial_withdraw_ix(accounts(), 0, LAMPORTS_PER_SOL + 1),
+        &owner,
+    );
+
+    assert_custom_error(result, VaultError::InsufficientFunds.into());
+}
+
+#[test]
+fn top_up_adds_to_the_vault() {
+    let (mut svm, owner) = setup();
+    let accounts = || vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+    let vault = accounts().vault;
+
+    send(
+        &mut svm,
+        deposit_ix(accounts(), 0, LAMPORTS_PER_SOL),
+        &owner,
+    )
+    .unwrap();
+    // later deposits don't have to cover rent on their own
+    send(&mut svm, top_up_ix(accounts(), 0, 1), &owner).unwrap();
+
+    assert_eq!(svm.get_balance(&vault), Some(LAMPORTS_PER_SOL + 1));
+}
+
+#[test]
+fn top_up_opening_a_vault_below_rent_minimum_fails() {
+    let (mut svm, owner) = setup();
+    let accounts = vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+    let rent = svm.minimum_balance_for_rent_exemption(0);
+
+    let result = send(&mut svm, top_up_ix(accounts, 0, rent), &owner);
+
+    assert_custom_error(result, VaultError::InvalidAmount.into());
+}
+
+#[test]
+fn locked_vault_withdraws_after_unlock() {
+    let (mut svm, owner) = setup();
+    let accounts = || vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+    let vault = accounts().vault;
+    let unlock_at = now(&svm) + 60;
+
+    send(
+        &mut svm,
+        deposit_locked_ix(accounts(), 0, LAMPORTS_PER_SOL, unlock_at),
+        &owner,
+    )
+    .unwrap();
+
+    let result = send(&mut svm, withdraw_ix(accounts(), 0), &owner);
+    assert_custom_error(result, VaultError::VaultLocked.into());
+
+    warp_to(&mut svm, unlock_at);
+    send(&mut svm, withdraw_ix(accounts(), 0), &owner).unwrap();
+
+    assert_eq!(svm.get_balance(&vault).unwrap_or(0), 0);
+}
+
+#[test]
+fn extend_lock_cannot_shorten() {
+    let (mut svm, owner) = setup();
+    let accounts = || vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+    let vault_state = accounts().vault_state;
+    let unlock_at = now(&svm) + 60;
+
+    send(
+        &mut svm,
+        deposit_locked_ix(accounts(), 0, LAMPORTS_PER_SOL, unlock_at),
+        &owner,
+    )
+    .unwrap();
+
+    let result = send(
+        &mut svm,
+        extend_lock_ix(&owner.pubkey(), vault_state, unlock_at - 1),
+        &owner,
+    );
+    assert_custom_error(result, VaultError::InvalidUnlockTime.into());
+
+    send(
+        &mut svm,
+        extend_lock_ix(&owner.pubkey(), vault_state, unlock_at + 60),
+        &owner,
+    )
+    .unwrap();
+    warp_to(&mut svm, unlock_at);
+
+    let result = send(&mut svm, withdraw_ix(accounts(), 0), &owner);
+    assert_custom_error(result, VaultError::VaultLocked.into());
+}
+
+#[test]
+fn deposit_locked_into_an_emptied_vault_cannot_shorten_the_lock() {
+    let (mut svm, owner) = setup();
+    let accounts = || vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+    let unlock_at = now(&svm) + 60;
+
+    send(
+        &mut svm,
+        deposit_locked_ix(accounts(), 0, LAMPORTS_PER_SOL, unlock_at),
+        &owner,
+    )
+    .unwrap();
+
+    // stands in for a locked vault whose balance left some other way
+    svm.set_account(accounts().vault, Default::default())
+        .unwrap();
+
+    let result = send(
+        &mut svm,
+        deposit_locked_ix(accounts(), 0, LAMPORTS_PER_SOL, unlock_at - 30),
+        &owner,
+    );
+
+    assert_custom_error(result, VaultError::InvalidUnlockTime.into());
+}
+
+#[test]
+fn guardians_approve_withdrawals_over_the_budget() {
+    let (mut svm, owner) = setup();
+    let accounts = || vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+    let (vault, vault_state) = (accounts().vault, accounts().vault_state);
+    let guardian = Keypair::new();
+
+    send(
+        &mut svm,
+        deposit_ix(accounts(), 0, 2 * LAMPORTS_PER_SOL),
+        &owner,
+    )
+    .unwrap();
+    send(
+        &mut svm,
+        set_guardians_ix(
+            &owner.pubkey(),
+            vault_state,
+            vec![guardian.pubkey()],
+            1,
+            LAMPORTS_PER_SOL / 2,
+            &[],
+        ),
+        &owner,
+    )
+    .unwrap();
+
+    send(
+        &mut svm,
+        partial_withdraw_ix(accounts(), 0, LAMPORTS_PER_SOL * 2 / 5),
+        &owner,
+    )
+    .unwrap();
+
+    // counted across withdrawals, so splitting one up doesn't get around it
+    let result = send(
+        &mut svm,
+        partial_withdraw_ix(accounts(), 0, LAMPORTS_PER_SOL / 5),
+        &owner,
+    );
+    assert_custom_error(result, VaultError::GuardianApprovalRequired.into());
+
+    send(
+        &mut svm,
+        propose_withdraw_ix(&owner.pubkey(), vault_state, 0, LAMPORTS_PER_SOL),
+        &owner,
+    )
+    .unwrap();
+
+    let result = send(&mut svm, execute_withdraw_ix(accounts(), 0), &owner);
+    assert_custom_error(result, VaultError::NotEnoughApprovals.into());
+
+    send_signed(
+        &mut svm,
+        approve_withdraw_ix(&guardian.pubkey(), vault_state, 0),
+        &[&owner, &guardian],
+    )
+    .unwrap();
+    send(&mut svm, execute_withdraw_ix(accounts(), 0), &owner).unwrap();
+
+    assert_eq!(svm.get_balance(&vault), Some(LAMPORTS_PER_SOL * 3 / 5));
+}
+
+#[test]
+fn approvals_survive_a_full_guardian_rotation() {
+    let (mut svm, owner) = setup();
+    let accounts = || vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+    let vault_state = accounts().vault_state;
+    let old_guardians: Vec<Keypair> = (0..5).map(|_| Keypair::new()).collect();
+    let new_guardians: Vec<Keypair> = (0..5).map(|_| Keypair::new()).collect();
+
+    send(
+        &mut svm,
+        deposit_ix(accounts(), 0, 2 * LAMPORTS_PER_SOL),
+        &owner,
+    )
+    .unwrap();
+    send(
+        &mut svm,
+        set_guardians_ix(
+            &owner.pubkey(),
+            vault_state,
+            old_guardians.iter().map(|g| g.pubkey()).collect(),
+            5,
+            0,
+            &[],
+        ),
+        &owner,
+    )
+    .unwrap();
+    send(
+        &mut svm,
+        propose_withdraw_ix(&owner.pubkey(), vault_state, 0, LAMPORTS_PER_SOL),
+        &owner,
+    )
+    .unwrap();
+
+    for guardian in &old_guardians {
+        send_signed(
+            &mut svm,
+            approve_withdraw_ix(&guardian.pubkey(), vault_state, 0),
+            &[&owner, guardian],
+        )
+        .unwrap();
+    }
+
+    let mut signers = vec![&owner];
+    signers.extend(old_guardians.iter());
+    send_signed(
+        &mut svm,
+        set_guardians_ix(
+            &owner.pubkey(),
+            vault_state,
+            new_guardians.iter().map(|g| g.pubkey()).collect(),
+            5,
+            0,
+            &old_guardians.iter().map(|g| g.pubkey()).collect::<Vec<_>>(),
+        ),
+        &signers,
+    )
+    .unwrap();
+
+    // the proposal already holds MAX_GUARDIANS approvals, all from guardians
+    // that are gone now
+    for guardian in &new_guardians {
+        send_signed(
+            &mut svm,
+            approve_withdraw_ix(&guardian.pubkey(), vault_state, 0),
+            &[&owner, guardian],
+        )
+        .unwrap();
+    }
+
+    send(&mut svm, execute_withdraw_ix(accounts(), 0), &owner).unwrap();
+}
+
+#[test]
+fn token_deposit_and_withdraw_with_either_token_program() {
+    for token_program in [spl_token::ID, spl_token_2022::ID] {
+        let (mut svm, owner) = setup();
+        let vault = vault_action(&owner.pubkey(), &owner.pubkey(), 0).vault;
+        let mint = create_mint(&mut svm, &owner, &token_program);
+        mint_tokens(
+            &mut svm,
+            &owner,
+            &mint,
+            &owner.pubkey(),
+            &token_program,
+            1_000,
+        );
+
+        send(
+            &mut svm,
+            deposit_token_ix(&owner.pubkey(), 0, &mint, &token_program, 100),
+            &owner,
+        )
+        .unwrap();
+        send(
+            &mut svm,
+            withdraw_token_ix(&owner.pubkey(), 0, &mint, &token_program, 40),
+            &owner,
+        )
+        .unwrap();
+
+        assert_eq!(token_balance(&svm, &vault, &mint, &token_program), 60);
+        assert_eq!(
+            token_balance(&svm, &owner.pubkey(), &mint, &token_program),
+            940
+        );
+
+        let result = send(
+            &mut svm,
+            withdraw_token_ix(&owner.pubkey(), 0, &mint, &token_program, 61),
+            &owner,
+        );
+        assert_custom_error(result, VaultError::InsufficientFunds.into());
+    }
+}
+
+#[test]
+fn delegate_withdraws_up_to_its_allowance() {
+    let (mut svm, owner) = setup();
+    let accounts = || vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+    let delegate = Keypair::new();
+    svm.airdrop(&delegate.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+    send(
+        &mut svm,
+        deposit_ix(accounts(), 0, 2 * LAMPORTS_PER_SOL),
+        &owner,
+    )
+    .unwrap();
+    send(
+        &mut svm,
+        approve_delegate_ix(accounts(), &delegate.pubkey(), LAMPORTS_PER_SOL / 2, 0),
+        &owner,
+    )
+    .unwrap();
+
+    let before = svm.get_balance(&delegate.pubkey()).unwrap();
+    send(
+        &mut svm,
+        delegate_withdraw_ix(accounts(), &delegate.pubkey(), LAMPORTS_PER_SOL / 4),
+        &delegate,
+    )
+    .unwrap();
+    assert!(svm.get_balance(&delegate.pubkey()).unwrap() > before);
+
+    // the allowance is spent down, not reset per withdrawal
+    let result = send(
+        &mut svm,
+        delegate_withdraw_ix(accounts(), &delegate.pubkey(), LAMPORTS_PER_SOL / 2),
+        &delegate,
+    );
+    assert_custom_error(result, VaultError::AllowanceExceeded.into());
+}
+
+#[test]
+fn expired_or_revoked_delegate_cannot_withdraw() {
+    let (mut svm, owner) = setup();
+    let accounts = || vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+    let delegate = Keypair::new();
+    svm.airdrop(&delegate.pubkey(), LAMPORTS_PER_SOL).unwrap();
+    let expires_at = now(&svm) + 60;
+
+    send(
+        &mut svm,
+        deposit_ix(accounts(), 0, 2 * LAMPORTS_PER_SOL),
+        &owner,
+    )
+    .unwrap();
+    send(
+        &mut svm,
+        approve_delegate_ix(accounts(), &delegate.pubkey(), LAMPORTS_PER_SOL, expires_at),
+        &owner,
+    )
+    .unwrap();
+
+    warp_to(&mut svm, expires_at);
+    let result = send(
+        &mut svm,
+        delegate_withdraw_ix(accounts(), &delegate.pubkey(), 1),
+        &delegate,
+    );
+    assert_custom_error(result, VaultError::DelegationExpired.into());
+
+    send(
+        &mut svm,
+        revoke_delegate_ix(accounts(), &delegate.pubkey()),
+        &owner,
+    )
+    .unwrap();
+    let result = send(
+        &mut svm,
+        delegate_withdraw_ix(accounts(), &delegate.pubkey(), 1),
+        &delegate,
+    );
+    assert_custom_error(
+        result,
+        anchor_lang::error::ErrorCode::AccountNotInitialized.into(),
+    );
+}
+
+#[test]
+fn spend_limit_holds_over_a_rolling_window() {
+    let (mut svm, owner) = setup();
+    let accounts = || vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+    let start = now(&svm);
+
+    send(
+        &mut svm,
+        deposit_ix(accounts(), 0, 3 * LAMPORTS_PER_SOL),
+        &owner,
+    )
+    .unwrap();
+    // no limit yet, so setting one applies right away
+    send(
+        &mut svm,
+        set_spend_policy_ix(
+            &owner.pubkey(),
+            accounts().vault_state,
+            LAMPORTS_PER_SOL,
+            60,
+        ),
+        &owner,
+    )
+    .unwrap();
+
+    send(
+        &mut svm,
+        partial_withdraw_ix(accounts(), 0, LAMPORTS_PER_SOL * 3 / 5),
+        &owner,
+    )
+    .unwrap();
+
+    warp_to(&mut svm, start + 59);
+    let result = send(
+        &mut svm,
+        partial_withdraw_ix(accounts(), 0, LAMPORTS_PER_SOL * 3 / 5),
+        &owner,
+    );
+    assert_custom_error(result, VaultError::RateLimitExceeded.into());
+
+    warp_to(&mut svm, start + 60);
+    send(
+        &mut svm,
+        partial_withdraw_ix(accounts(), 0, LAMPORTS_PER_SOL * 3 / 5),
+        &owner,
+    )
+    .unwrap();
+}
+
+#[test]
+fn beneficiary_claims_after_the_owner_goes_quiet() {
+    let (mut svm, owner) = setup();
+    let accounts = || vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+    let vault_state = accounts().vault_state;
+    let beneficiary = Keypair::new();
+    svm.airdrop(&beneficiary.pubkey(), LAMPORTS_PER_SOL)
+        .unwrap();
+    let start = now(&svm);
+
+    send(
+        &mut svm,
+        deposit_ix(accounts(), 0, 2 * LAMPORTS_PER_SOL),
+        &owner,
+    )
+    .unwrap();
+    send(
+        &mut svm,
+        set_beneficiary_ix(
+            &owner.pubkey(),
+            vault_state,
+            Some(beneficiary.pubkey()),
+            MIN_INACTIVITY_PERIOD,
+        ),
+        &owner,
+    )
+    .unwrap();
+
+    // a new beneficiary only takes over once the change delay has passed
+    let result = send(
+        &mut svm,
+        claim_inheritance_ix(accounts(), &beneficiary.pubkey(), LAMPORTS_PER_SOL),
+        &beneficiary,
+    );
+    assert_custom_error(result, VaultError::InvalidBeneficiary.into());
+
+    warp_to(&mut svm, start + MIN_INACTIVITY_PERIOD / 2);
+    send(&mut svm, heartbeat_ix(&owner.pubkey(), vault_state), &owner).unwrap();
+
+    warp_to(&mut svm, start + MIN_INACTIVITY_PERIOD);
+    let result = send(
+        &mut svm,
+        claim_inheritance_ix(accounts(), &beneficiary.pubkey(), LAMPORTS_PER_SOL),
+        &beneficiary,
+    );
+    assert_custom_error(result, VaultError::OwnerStillActive.into());
+
+    warp_to(&mut svm, start + MIN_INACTIVITY_PERIOD * 3 / 2);
+    send(
+        &mut svm,
+        claim_inheritance_ix(accounts(), &beneficiary.pubkey(), LAMPORTS_PER_SOL),
+        &beneficiary,
+    )
+    .unwrap();
+
+    assert_eq!(svm.get_balance(&accounts().vault), Some(LAMPORTS_PER_SOL));
+}
+
+#[test]
+fn close_waits_for_delegations_and_proposals() {
+    let (mut svm, owner) = setup();
+    let accounts = || vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+    let vault_state = accounts().vault_state;
+    let delegate = Pubkey::new_unique();
+
+    send(
+        &mut svm,
+        deposit_ix(accounts(), 0, LAMPORTS_PER_SOL),
+        &owner,
+    )
+    .unwrap();
+    send(
+        &mut svm,
+        approve_delegate_ix(accounts(), &delegate, LAMPORTS_PER_SOL, 0),
+        &owner,
+    )
+    .unwrap();
+    send(&mut svm, withdraw_ix(accounts(), 0), &owner).unwrap();
+
+    let result = send(&mut svm, close_vault_ix(accounts()), &owner);
+    assert_custom_error(result, VaultError::VaultNotEmpty.into());
+
+    send(&mut svm, revoke_delegate_ix(accounts(), &delegate), &owner).unwrap();
+    send(
+        &mut svm,
+        propose_withdraw_ix(&owner.pubkey(), vault_state, 0, 1),
+        &owner,
+    )
+    .unwrap();
+
+    let result = send(&mut svm, close_vault_ix(accounts()), &owner);
+    assert_custom_error(result, VaultError::VaultNotEmpty.into());
+
+    send(
+        &mut svm,
+        cancel_proposal_ix(&owner.pubkey(), vault_state, 0),
+        &owner,
+    )
+    .unwrap();
+    send(&mut svm, close_vault_ix(accounts()), &owner).unwrap();
+    assert!(svm.get_account(&vault_state).is_none());
+
+    // the proposal ids restart on reopen, which is fine once nothing is left over
+    send(
+        &mut svm,
+        deposit_ix(accounts(), 0, LAMPORTS_PER_SOL),
+        &owner,
+    )
+    .unwrap();
+    send(
+        &mut svm,
+        propose_withdraw_ix(&owner.pubkey(), vault_state, 0, 1),
+        &owner,
+    )
+    .unwrap();
+}
+
+#[test]
+fn close_waits_for_token_balances() {
+    let token_program = spl_token::ID;
+    let (mut svm, owner) = setup();
+    let accounts = || vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+    let mint = create_mint(&mut svm, &owner, &token_program);
+    mint_tokens(
+        &mut svm,
+        &owner,
+        &mint,
+        &owner.pubkey(),
+        &token_program,
+        1_000,
+    );
+
+    send(
+        &mut svm,
+        deposit_token_ix(&owner.pubkey(), 0, &mint, &token_program, 100),
+        &owner,
+    )
+    .unwrap();
+
+    let result = send(&mut svm, close_vault_ix(accounts()), &owner);
+    assert_custom_error(result, VaultError::VaultNotEmpty.into());
+
+    send(
+        &mut svm,
+        withdraw_token_ix(&owner.pubkey(), 0, &mint, &token_program, 100),
+        &owner,
+    )
+    .unwrap();
+    send(&mut svm, close_vault_ix(accounts()), &owner).unwrap();
+}
+
+#[test]
+fn legacy_vault_withdraws_to_its_owner() {
+    let (mut svm, owner) = setup();
+    let legacy_vault = pda(&[b"vault", owner.pubkey().as_ref()]);
+    svm.airdrop(&legacy_vault, LAMPORTS_PER_SOL).unwrap();
+    let before = svm.get_balance(&owner.pubkey()).unwrap();
+
+    send(&mut svm, withdraw_legacy_ix(&owner.pubkey()), &owner).unwrap();
+
+    assert_eq!(svm.get_balance(&legacy_vault).unwrap_or(0), 0);
+    assert!(svm.get_balance(&owner.pubkey()).unwrap() > before);
+
+    let result = send(&mut svm, withdraw_legacy_ix(&owner.pubkey()), &owner);
+    assert_custom_error(result, VaultError::InvalidAmount.into());
+}
+
+#[test]
+fn new_owner_starts_without_delegates_or_beneficiary() {
+    let (mut svm, owner) = setup();
+    let accounts = || vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+    let vault_state = accounts().vault_state;
+    let new_owner = Keypair::new();
+    let delegate = Keypair::new();
+    let beneficiary = Keypair::new();
+    for account in [&new_owner, &delegate, &beneficiary] {
+        svm.airdrop(&account.pubkey(), LAMPORTS_PER_SOL).unwrap();
+    }
+    let start = now(&svm);
+
+    send(
+        &mut svm,
+        deposit_ix(accounts(), 0, 2 * LAMPORTS_PER_SOL),
+        &owner,
+    )
+    .unwrap();
+    send(
+        &mut svm,
+        approve_delegate_ix(accounts(), &delegate.pubkey(), LAMPORTS_PER_SOL, 0),
+        &owner,
+    )
+    .unwrap();
+    send(
+        &mut svm,
+        set_beneficiary_ix(
+            &owner.pubkey(),
+            vault_state,
+            Some(beneficiary.pubkey()),
+            MIN_INACTIVITY_PERIOD,
+        ),
+        &owner,
+    )
+    .unwrap();
+    send(
+        &mut svm,
+        propose_owner_ix(&owner.pubkey(), vault_state, Some(new_owner.pubkey())),
+        &owner,
+    )
+    .unwrap();
+    send(
+        &mut svm,
+        accept_ownership_ix(&new_owner.pubkey(), &owner.pubkey(), vault_state),
+        &new_owner,
+    )
+    .unwrap();
+
+    let result = send(
+        &mut svm,
+        delegate_withdraw_ix(accounts(), &delegate.pubkey(), 1),
+        &delegate,
+    );
+    assert_custom_error(result, VaultError::DelegationRevoked.into());
+
+    warp_to(&mut svm, start + MIN_INACTIVITY_PERIOD * 2);
+    let result = send(
+        &mut svm,
+        claim_inheritance_ix(accounts(), &beneficiary.pubkey(), 1),
+        &beneficiary,
+    );
+    assert_custom_error(result, VaultError::InvalidBeneficiary.into());
+
+    // the new owner can still close out the old delegation account
+    let new_owner_accounts = vault_action(&new_owner.pubkey(), &owner.pubkey(), 0);
+    send(
+        &mut svm,
+        revoke_delegate_ix(new_owner_accounts, &delegate.pubkey()),
+        &new_owner,
+    )
+    .unwrap();
+}
+
+#[test]
+fn staking_keeps_the_lock_and_the_vault_open() {
+    let (mut svm, owner) = setup();
+    let accounts = || vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+    let vote_account = Pubkey::new_unique();
+    let unlock_at = now(&svm) + 60;
+
+    send(
+        &mut svm,
+        deposit_locked_ix(accounts(), 0, LAMPORTS_PER_SOL, unlock_at),
+        &owner,
+    )
+    .unwrap();
+
+    let result = send(
+        &mut svm,
+        delegate_stake_ix(accounts(), &vote_account, LAMPORTS_PER_SOL / 2),
+        &owner,
+    );
+    assert_custom_error(result, VaultError::VaultLocked.into());
+
+    // staking the whole balance would leave the vault looking closed
+    warp_to(&mut svm, unlock_at);
+    let result = send(
+        &mut svm,
+        delegate_stake_ix(accounts(), &vote_account, LAMPORTS_PER_SOL),
+        &owner,
+    );
+    assert_custom_error(result, VaultError::InsufficientFunds.into());
+}
+
+#[test]
+fn queued_withdrawals_cover_lamports_and_tokens() {
+    let token_program = spl_token_2022::ID;
+    let (mut svm, owner) = setup();
+    let accounts = || vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+    let vault_state = accounts().vault_state;
+    let mint = create_mint(&mut svm, &owner, &token_program);
+    mint_tokens(
+        &mut svm,
+        &owner,
+        &mint,
+        &owner.pubkey(),
+        &token_program,
+        1_000,
+    );
+
+    send(
+        &mut svm,
+        deposit_ix(accounts(), 0, 2 * LAMPORTS_PER_SOL),
+        &owner,
+    )
+    .unwrap();
+    send(
+        &mut svm,
+        deposit_token_ix(&owner.pubkey(), 0, &mint, &token_program, 100),
+        &owner,
+    )
+    .unwrap();
+    send(
+        &mut svm,
+        set_queue_policy_ix(&owner.pubkey(), vault_state, true, 60),
+        &owner,
+    )
+    .unwrap();
+
+    let result = send(
+        &mut svm,
+        withdraw_token_ix(&owner.pubkey(), 0, &mint, &token_program, 40),
+        &owner,
+    );
+    assert_custom_error(result, VaultError::WithdrawQueueRequired.into());
+
+    let ready_at = now(&svm) + 60;
+    send(
+        &mut svm,
+        request_withdraw_ix(&owner.pubkey(), vault_state, 0, LAMPORTS_PER_SOL),
+        &owner,
+    )
+    .unwrap();
+    send(
+        &mut svm,
+        request_withdraw_token_ix(&owner.pubkey(), vault_state, 1, &mint, 40),
+        &owner,
+    )
+    .unwrap();
+
+    let result = send(
+        &mut svm,
+        finalize_withdraw_token_ix(accounts(), 1, &mint, &token_program),
+        &owner,
+    );
+    assert_custom_error(result, VaultError::WithdrawNotReady.into());
+
+    warp_to(&mut svm, ready_at);
+
+    // a request only pays out the asset it was made for
+    let result = send(
+        &mut svm,
+        finalize_withdraw_token_ix(accounts(), 0, &mint, &token_program),
+        &owner,
+    );
+    assert_custom_error(result, VaultError::RequestMintMismatch.into());
+    let result = send(&mut svm, finalize_withdraw_ix(accounts(), 1), &owner);
+    assert_custom_error(result, VaultError::RequestMintMismatch.into());
+
+    send(&mut svm, finalize_withdraw_ix(accounts(), 0), &owner).unwrap();
+    send(
+        &mut svm,
+        finalize_withdraw_token_ix(accounts(), 1, &mint, &token_program),
+        &owner,
+    )
+    .unwrap();
+
+    assert_eq!(svm.get_balance(&accounts().vault), Some(LAMPORTS_PER_SOL));
+    assert_eq!(
+        token_balance(&svm, &accounts().vault, &mint, &token_program),
+        60
+    );
+}
+
+#[test]
+fn withdraw_to_only_reaches_active_destinations() {
+    let (mut svm, owner) = setup();
+    let accounts = || vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+    let vault_state = accounts().vault_state;
+    let destination = Pubkey::new_unique();
+    let start = now(&svm);
+
+    send(
+        &mut svm,
+        deposit_ix(accounts(), 0, 2 * LAMPORTS_PER_SOL),
+        &owner,
+    )
+    .unwrap();
+    send(
+        &mut svm,
+        add_destination_ix(&owner.pubkey(), vault_state, destination),
+        &owner,
+    )
+    .unwrap();
+
+    // a freshly added destination waits out the delay, a leaked key can't
+    // add one and drain to it straight away
+    let result = send(
+        &mut svm,
+        withdraw_to_ix(accounts(), &destination, LAMPORTS_PER_SOL),
+        &owner,
+    );
+    assert_custom_error(result, VaultError::DestinationNotAllowed.into());
+
+    warp_to(&mut svm, start + DESTINATION_ADD_DELAY);
+    send(
+        &mut svm,
+        withdraw_to_ix(accounts(), &destination, LAMPORTS_PER_SOL),
+        &owner,
+    )
+    .unwrap();
+    assert_eq!(svm.get_balance(&destination), Some(LAMPORTS_PER_SOL));
+
+    let result = send(
+        &mut svm,
+        withdraw_to_ix(accounts(), &Pubkey::new_unique(), LAMPORTS_PER_SOL / 2),
+        &owner,
+    );
+    assert_custom_error(result, VaultError::DestinationNotAllowed.into());
+
+    send(
+        &mut svm,
+        remove_destination_ix(&owner.pubkey(), vault_state, destination),
+        &owner,
+    )
+    .unwrap();
+    let result = send(
+        &mut svm,
+        withdraw_to_ix(accounts(), &destination, LAMPORTS_PER_SOL / 2),
+        &owner,
+    );
+    assert_custom_error(result, VaultError::DestinationNotAllowed.into());
+}
+
+#[test]
+fn frozen_vault_waits_for_a_guardian_or_the_unfreeze_delay() {
+    let (mut svm, owner) = setup();
+    let accounts = || vault_action(&owner.pubkey(), &owner.pubkey(), 0);
+    let vault_state = accounts().vault_state;
+    let guardian = Keypair::new();
+    svm.airdrop(&guardian.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+    send(
+        &mut svm,
+        deposit_ix(accounts(), 0, 2 * LAMPORTS_PER_SOL),
+        &owner,
+    )
+    .unwrap();
+    send(
+        &mut svm,
+        set_guardians_ix(
+            &owner.pubkey(),
+            vault_state,
+            vec![guardian.pubkey()],
+            1,
+            2 * LAMPORTS_PER_SOL,
+            &[],
+        ),
+        &owner,
+    )
+    .unwrap();
+
+    send(
+        &mut svm,
+        freeze_ix(&guardian.pubkey(), vault_state),
+        &guardian,
+    )
+    .unwrap();
+    let result = send(
+        &mut svm,
+        partial_withdraw_ix(accounts(), 0, LAMPORTS_PER_SOL / 2),
+        &owner,
+    );
+    assert_custom_error(result, VaultError::VaultFrozen.into());
+
+    // the owner can only unfreeze after asking and waiting out the delay
+    let result = send(&mut svm, unfreeze_ix(&owner.pubkey(), vault_state), &owner);
+    assert_custom_error(result, VaultError::VaultFrozen.into());
+
+    let requested_at = now(&svm);
+    send(
+        &mut svm,
+        request_unfreeze_ix(&owner.pubkey(), vault_state),
+        &owner,
+    )
+    .unwrap();
+    let result = send(&mut svm, unfreeze_ix(&owner.pubkey(), vault_state), &owner);
+    assert_custom_error(result, VaultError::VaultFrozen.into());
+
+    warp_to(&mut svm, requested_at + UNFREEZE_DELAY);
+    send(&mut svm, unfreeze_ix(&owner.pubkey(), vault_state), &owner).unwrap();
+    send(
+        &mut svm,
+        partial_withdraw_ix(accounts(), 0, LAMPORTS_PER_SOL / 2),
+        &owner,
+    )
+    .unwrap();
+
+    // a guardian doesn't have to wait
+    send(&mut svm, freeze_ix(&owner.pubkey(), vault_state), &owner).unwrap();
+    send(
+        &mut svm,
+        unfreeze_ix(&guardian.pubkey(), vault_state),
+        &guardian,
+    )
+    .unwrap();
+    send(
+        &mut svm,
+        partial_withdraw_ix(accounts(), 0, LAMPORTS_PER_SOL / 2),
+        &owner,
+    )
+    .unwrap();
+}